@@ -0,0 +1,336 @@
+//! Contains sprite animation code. See the crate level documentation.
+
+///How a clip continues once it reaches its last frame.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PlayMode {
+    ///Start over from the first frame.
+    Loop,
+    ///Play the frames backwards back to the first one, then forwards again, and so on.
+    ///The first and last frames are not repeated at the turns.
+    PingPong,
+    ///Stay on the last frame.
+    Once,
+}
+
+///One tile of a clip and how long it is shown for.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Frame {
+    ///The tile index as passed to `SpriteSession::add`.
+    pub index: u16,
+    pub duration: f32,
+}
+
+///A sequence of tiles in a tile set that make up an animation.
+///Durations are in whatever unit of time the user steps the animation by, for example seconds.
+#[derive(Clone, Debug)]
+pub struct Clip {
+    frames: Vec<Frame>,
+    mode: PlayMode,
+    length: f32,
+}
+
+impl Clip {
+    ///Panics if there are no frames. Negative durations are treated as zero.
+    pub fn new(frames: &[Frame], mode: PlayMode) -> Clip {
+        assert!(!frames.is_empty(), "a clip needs at least one frame");
+        let frames: Vec<Frame> = frames
+            .iter()
+            .map(|f| Frame {
+                index: f.index,
+                duration: f.duration.max(0.0),
+            })
+            .collect();
+        let length = frames.iter().map(|f| f.duration).sum();
+        Clip {
+            frames,
+            mode,
+            length,
+        }
+    }
+
+    ///Create a clip where every frame is shown for the same amount of time.
+    ///Since tile indices go left to right and then top to bottom,
+    ///a range of indices is often all that is needed, for example `Clip::uniform(8..14, 0.1, PlayMode::Loop)`.
+    ///Panics if there are no frames.
+    pub fn uniform(
+        indices: impl IntoIterator<Item = u16>,
+        frame_duration: f32,
+        mode: PlayMode,
+    ) -> Clip {
+        let frames: Vec<Frame> = indices
+            .into_iter()
+            .map(|index| Frame {
+                index,
+                duration: frame_duration,
+            })
+            .collect();
+        Clip::new(&frames, mode)
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    pub fn mode(&self) -> PlayMode {
+        self.mode
+    }
+
+    ///How long it takes to play every frame once.
+    pub fn length(&self) -> f32 {
+        self.length
+    }
+
+    ///How long it takes before a looping clip starts over.
+    ///For ping pong clips this includes playing the frames backwards.
+    pub fn cycle_length(&self) -> f32 {
+        match self.mode {
+            PlayMode::PingPong => {
+                let back: f32 = self.inner_frames().iter().map(|f| f.duration).sum();
+                self.length + back
+            }
+            PlayMode::Loop | PlayMode::Once => self.length,
+        }
+    }
+
+    ///Whether a clip that plays once has reached its end by the given time.
+    ///Looping and ping pong clips never finish.
+    pub fn is_finished(&self, time: f32) -> bool {
+        self.mode == PlayMode::Once && time >= self.length
+    }
+
+    ///The tile index to draw at the given time since the clip started.
+    ///Negative times count back from the start, so clips can be played in reverse.
+    pub fn index_at(&self, time: f32) -> u16 {
+        let cycle = self.cycle_length();
+        if cycle <= 0.0 || !time.is_finite() {
+            return self.frames[0].index;
+        }
+        match self.mode {
+            PlayMode::Once => {
+                if time >= self.length {
+                    self.frames[self.frames.len() - 1].index
+                } else {
+                    Self::lookup(&self.frames, time.max(0.0))
+                }
+            }
+            PlayMode::Loop => Self::lookup(&self.frames, Self::wrap(time, cycle)),
+            PlayMode::PingPong => {
+                let t = Self::wrap(time, cycle);
+                if t < self.length {
+                    Self::lookup(&self.frames, t)
+                } else {
+                    //On the way back, the frames between the first and last are played in reverse.
+                    let back = self.inner_frames();
+                    let t = t - self.length;
+                    let mut acc = 0.0;
+                    for f in back.iter().rev() {
+                        acc += f.duration;
+                        if t < acc {
+                            return f.index;
+                        }
+                    }
+                    self.frames[0].index
+                }
+            }
+        }
+    }
+
+    ///The time into the current cycle.
+    ///`rem_euclid` rounds up to the cycle itself for tiny negative times, which is the start of the next cycle.
+    fn wrap(time: f32, cycle: f32) -> f32 {
+        let t = time.rem_euclid(cycle);
+        if t >= cycle {
+            0.0
+        } else {
+            t
+        }
+    }
+
+    ///Every frame but the first and last.
+    fn inner_frames(&self) -> &[Frame] {
+        let n = self.frames.len();
+        if n > 2 {
+            &self.frames[1..n - 1]
+        } else {
+            &[]
+        }
+    }
+
+    ///The frame that is showing the given time into a single pass over the frames.
+    fn lookup(frames: &[Frame], time: f32) -> u16 {
+        let mut acc = 0.0;
+        for f in frames.iter() {
+            acc += f.duration;
+            if time < acc {
+                return f.index;
+            }
+        }
+        frames[frames.len() - 1].index
+    }
+}
+
+///Keeps track of how far along a clip a sprite is.
+///The player does not hold on to a clip, so one clip can be shared by many sprites,
+///and the clip a sprite plays can be switched without losing its place.
+///Time is kept in double precision, so small steps are not lost after hours of play.
+#[derive(Copy, Clone, Debug)]
+pub struct Player {
+    time: f64,
+    speed: f32,
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Player {
+    pub fn new() -> Self {
+        Player {
+            time: 0.0,
+            speed: 1.0,
+        }
+    }
+
+    ///Set how fast time passes for the clip. A negative speed plays it in reverse.
+    pub fn with_speed(&mut self, speed: f32) -> &mut Self {
+        self.speed = speed;
+        self
+    }
+
+    ///Advance the animation by the time passed since the last update.
+    pub fn update(&mut self, delta: f32) -> &mut Self {
+        self.time += f64::from(delta * self.speed);
+        self
+    }
+
+    ///Go back to the first frame.
+    pub fn restart(&mut self) -> &mut Self {
+        self.time = 0.0;
+        self
+    }
+
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    pub fn set_time(&mut self, time: f64) -> &mut Self {
+        self.time = time;
+        self
+    }
+
+    ///The time into the clip, brought into its first cycle before it loses precision as a `f32`.
+    fn clip_time(&self, clip: &Clip) -> f32 {
+        let cycle = f64::from(clip.cycle_length());
+        match clip.mode() {
+            PlayMode::Loop | PlayMode::PingPong if cycle > 0.0 => {
+                self.time.rem_euclid(cycle) as f32
+            }
+            _ => self.time.max(0.0).min(f64::from(clip.length())) as f32,
+        }
+    }
+
+    ///The tile index of the clip to pass to `SpriteSession::add`.
+    pub fn index(&self, clip: &Clip) -> u16 {
+        clip.index_at(self.clip_time(clip))
+    }
+
+    ///Whether the clip plays once and has reached its end.
+    pub fn is_finished(&self, clip: &Clip) -> bool {
+        clip.is_finished(self.clip_time(clip))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn indices(clip: &Clip, times: &[f32]) -> Vec<u16> {
+        times.iter().map(|&t| clip.index_at(t)).collect()
+    }
+
+    #[test]
+    fn ping_pong_short_clips() {
+        let one = Clip::uniform(5..6, 1.0, PlayMode::PingPong);
+        assert_eq!(one.cycle_length(), 1.0);
+        assert_eq!(indices(&one, &[0.0, 0.5, 1.5, -3.2]), vec![5, 5, 5, 5]);
+
+        //With two frames there is nothing in between to play backwards.
+        let two = Clip::uniform(5..7, 1.0, PlayMode::PingPong);
+        assert_eq!(two.cycle_length(), 2.0);
+        assert_eq!(indices(&two, &[0.5, 1.5, 2.5, 3.5]), vec![5, 6, 5, 6]);
+
+        let four = Clip::uniform(0..4, 1.0, PlayMode::PingPong);
+        let times: Vec<f32> = (0..8).map(|t| t as f32 + 0.5).collect();
+        assert_eq!(indices(&four, &times), vec![0, 1, 2, 3, 2, 1, 0, 1]);
+    }
+
+    #[test]
+    fn zero_duration_frames() {
+        let frames = [
+            Frame { index: 0, duration: 1.0 },
+            Frame { index: 1, duration: 0.0 },
+            Frame { index: 2, duration: 1.0 },
+        ];
+        let clip = Clip::new(&frames, PlayMode::Loop);
+        assert_eq!(indices(&clip, &[0.5, 1.0, 1.5, 2.0]), vec![0, 2, 2, 0]);
+
+        let clip = Clip::new(&frames, PlayMode::PingPong);
+        assert_eq!(clip.cycle_length(), 2.0);
+        assert_eq!(indices(&clip, &[0.5, 1.5, 2.5]), vec![0, 2, 0]);
+
+        //A clip with no length always shows its first frame.
+        let clip = Clip::uniform(3..6, 0.0, PlayMode::Loop);
+        assert_eq!(indices(&clip, &[0.0, 1.0, -1.0]), vec![3, 3, 3]);
+        let clip = Clip::uniform(3..6, -1.0, PlayMode::Once);
+        assert_eq!(clip.length(), 0.0);
+        assert_eq!(indices(&clip, &[0.0, 1.0]), vec![3, 3]);
+    }
+
+    #[test]
+    fn negative_time() {
+        let clip = Clip::uniform(0..4, 1.0, PlayMode::Loop);
+        assert_eq!(indices(&clip, &[-0.5, -1.5, -4.5]), vec![3, 2, 3]);
+
+        let clip = Clip::uniform(0..4, 1.0, PlayMode::PingPong);
+        assert_eq!(indices(&clip, &[-0.5, -1.5, -2.5]), vec![1, 2, 3]);
+
+        let clip = Clip::uniform(0..4, 1.0, PlayMode::Once);
+        assert_eq!(indices(&clip, &[-5.0, 3.5, 100.0]), vec![0, 3, 3]);
+        assert!(!clip.is_finished(3.9));
+        assert!(clip.is_finished(4.0));
+    }
+
+    #[test]
+    fn wrap_at_cycle() {
+        //The remainder of a tiny negative time rounds up to the whole cycle.
+        assert_eq!((-1e-8f32).rem_euclid(4.0), 4.0);
+        for mode in [PlayMode::Loop, PlayMode::PingPong].iter() {
+            let clip = Clip::uniform(0..4, 1.0, *mode);
+            assert_eq!(clip.index_at(-1e-8), 0);
+        }
+    }
+
+    #[test]
+    fn player_keeps_precision() {
+        let clip = Clip::uniform(0..4, 0.1, PlayMode::Loop);
+        let mut player = Player::new();
+        player.set_time(1.0e7 + 0.05);
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            seen.push(player.index(&clip));
+            player.update(0.1);
+        }
+        seen.sort();
+        seen.dedup();
+        assert_eq!(seen.len(), 4);
+
+        let once = Clip::uniform(0..4, 0.1, PlayMode::Once);
+        assert!(player.is_finished(&once));
+        assert_eq!(player.index(&once), 3);
+
+        player.restart().with_speed(-1.0).update(0.05);
+        assert_eq!(player.index(&clip), 3);
+    }
+}
@@ -52,10 +52,8 @@ impl<T, F: Fn(&T) -> &[f32; 2]> BatchCircle<T, F> {
             offset: vec2same(0.0),
         };
         let un = ProgramUniformValues {
-            mode: gl::POINTS,
-            radius,
             stride,
-            texture: None,
+            ..ProgramUniformValues::new(radius, gl::POINTS)
         };
 
         Uniforms {
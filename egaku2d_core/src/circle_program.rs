@@ -11,6 +11,31 @@ pub struct ProgramUniformValues<'a> {
     pub mode: u32,
     pub stride: i32,
     pub texture: Option<(&'a sprite::Texture, f32, [f32; 2])>,
+    pub texture_space: sprite::TextureSpace,
+    ///Overrides the scale and offset of the texture if set.
+    pub texture_transform: Option<[[f32; 3]; 2]>,
+    pub antialias: bool,
+    ///Whether the buffer is made up of `EdgeVertex` instead of `Vertex`.
+    pub edges: bool,
+    ///Inner radius of a ring as a fraction of the outer radius. 0 draws a filled circle.
+    pub inner: f32,
+    ///How much each axis of a circle is squashed by to make an ellipse.
+    pub axis_scale: [f32; 2],
+    ///Whether the last four bytes of each vertex are a packed RGBA8 color.
+    pub colors: bool,
+    ///Whether the buffer is made up of `SizedVertex` that each have their own radius.
+    pub sizes: bool,
+    ///The biggest size of any vertex in the buffer, which the radius is multiplied by.
+    ///1 if the vertices have no size.
+    pub max_size: f32,
+    ///Draw each point as a quad instead, for points bigger than the driver can draw.
+    pub quad: bool,
+    ///Fill with a gradient instead of the flat color.
+    pub gradient: Option<Gradient>,
+    ///Whether the edge distances are also the position within each shape, as they are for rects.
+    pub local_edges: bool,
+    ///Whether the buffer is made up of `UvVertex` that each know where they are within their shape.
+    pub uvs: bool,
 }
 impl<'a> ProgramUniformValues<'a> {
     pub fn new(radius: f32, mode: u32) -> Self {
@@ -18,23 +43,189 @@ impl<'a> ProgramUniformValues<'a> {
             mode,
             radius,
             texture: None,
+            texture_space: sprite::TextureSpace::Screen,
+            texture_transform: None,
             stride: 0,
+            antialias: false,
+            edges: false,
+            inner: 0.0,
+            axis_scale: [1.0; 2],
+            colors: false,
+            sizes: false,
+            max_size: 1.0,
+            quad: false,
+            gradient: None,
+            local_edges: false,
+            uvs: false,
         }
     }
+    pub fn new_with_edges(radius: f32, mode: u32) -> Self {
+        ProgramUniformValues {
+            stride: core::mem::size_of::<EdgeVertex>() as i32,
+            edges: true,
+            ..Self::new(radius, mode)
+        }
+    }
+    pub fn new_with_colors(radius: f32, mode: u32) -> Self {
+        ProgramUniformValues {
+            stride: core::mem::size_of::<ColoredVertex>() as i32,
+            colors: true,
+            ..Self::new(radius, mode)
+        }
+    }
+    ///The radius of each point comes from its `SizedVertex` instead of from a uniform.
+    ///`max_size` is the biggest of those radii.
+    pub fn new_with_sizes(mode: u32, max_size: f32) -> Self {
+        ProgramUniformValues {
+            stride: core::mem::size_of::<SizedVertex>() as i32,
+            sizes: true,
+            max_size,
+            ..Self::new(1.0, mode)
+        }
+    }
+    pub fn new_with_uvs(radius: f32, mode: u32) -> Self {
+        ProgramUniformValues {
+            stride: core::mem::size_of::<UvVertex>() as i32,
+            uvs: true,
+            ..Self::new(radius, mode)
+        }
+    }
+    pub fn new_with_edges_and_colors(radius: f32, mode: u32) -> Self {
+        ProgramUniformValues {
+            stride: core::mem::size_of::<ColoredEdgeVertex>() as i32,
+            edges: true,
+            colors: true,
+            ..Self::new(radius, mode)
+        }
+    }
+}
+
+///The most color stops a gradient can have. Any more are ignored.
+pub const MAX_GRADIENT_STOPS: usize = 8;
+
+///A linear or radial gradient with up to `MAX_GRADIENT_STOPS` color stops.
+#[derive(Copy, Clone, Debug)]
+pub struct Gradient {
+    pub radial: bool,
+    ///The start of a linear gradient or the center of a radial one.
+    pub start: [f32; 2],
+    ///The end of a linear gradient. The x component is the radius of a radial one.
+    pub end: [f32; 2],
+    pub count: usize,
+    pub offsets: [f32; MAX_GRADIENT_STOPS],
+    pub colors: [[f32; 4]; MAX_GRADIENT_STOPS],
+}
+
+impl Gradient {
+    ///Returns None if there are no stops.
+    ///The stops are sorted by their offset, which is clamped to [0,1].
+    pub fn new(radial: bool, start: [f32; 2], end: [f32; 2], stops: &[(f32, [f32; 4])]) -> Option<Self> {
+        if stops.is_empty() {
+            return None;
+        }
+        let mut stops: Vec<(f32, [f32; 4])> = stops
+            .iter()
+            .take(MAX_GRADIENT_STOPS)
+            .map(|&(offset, color)| (offset.max(0.0).min(1.0), color))
+            .collect();
+        stops.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(core::cmp::Ordering::Equal));
+
+        let mut offsets = [0.0; MAX_GRADIENT_STOPS];
+        let mut colors = [[0.0; 4]; MAX_GRADIENT_STOPS];
+        for (i, &(offset, color)) in stops.iter().enumerate() {
+            offsets[i] = offset;
+            colors[i] = color;
+        }
+        Some(Gradient {
+            radial,
+            start,
+            end,
+            count: stops.len(),
+            offsets,
+            colors,
+        })
+    }
 }
 
 // Shader sources
 pub static VS_SRC: &'static str = "
 #version 300 es
 in vec2 position;
+in vec4 edge;
+in float size;
 out vec2 pos;
+out vec4 edge_dist;
+out vec2 quad_coord;
+out float ps;
+out vec2 world_pos;
+out float point_extent;
 uniform vec2 offset;
 uniform mat3 mmatrix;
 uniform float point_size;
+uniform bool quad;
+uniform float point_mul;
+
+//The corners of the two triangles drawn in place of a point that is too big.
+const vec2 CORNERS[6]=vec2[6](
+    vec2(0.0,0.0),vec2(1.0,0.0),vec2(0.0,1.0),
+    vec2(0.0,1.0),vec2(1.0,0.0),vec2(1.0,1.0)
+);
 void main() {
-    gl_PointSize = point_size;
-    vec3 pp=vec3(position+offset,1.0);
+    gl_PointSize = point_size*size;
+    vec2 p=position+offset;
+    quad_coord=vec2(0.0,0.0);
+    if(quad){
+        //Each instance is a quad the size of the point it replaces.
+        quad_coord=CORNERS[gl_VertexID];
+        p+=(quad_coord-vec2(0.5,0.5))*gl_PointSize/point_mul;
+    }
+    vec3 pp=vec3(p,1.0);
     pos=position*0.005;
+    edge_dist=edge;
+    ps=gl_PointSize;
+    world_pos=position;
+    //How big a point is in the same coordinates as the vertices. 0 when not drawing points.
+    point_extent=gl_PointSize/point_mul;
+    gl_Position = vec4(mmatrix*pp.xyz, 1.0);
+}";
+
+///The same as `VS_SRC` except that it also passes a per vertex color on to the fragment shader.
+pub static COLORED_VS_SRC: &'static str = "
+#version 300 es
+in vec2 position;
+in vec4 edge;
+in vec4 color;
+in float size;
+out vec2 pos;
+out vec4 edge_dist;
+out vec4 vcol;
+out vec2 quad_coord;
+out float ps;
+uniform vec2 offset;
+uniform mat3 mmatrix;
+uniform float point_size;
+uniform bool quad;
+uniform float point_mul;
+
+//The corners of the two triangles drawn in place of a point that is too big.
+const vec2 CORNERS[6]=vec2[6](
+    vec2(0.0,0.0),vec2(1.0,0.0),vec2(0.0,1.0),
+    vec2(0.0,1.0),vec2(1.0,0.0),vec2(1.0,1.0)
+);
+void main() {
+    gl_PointSize = point_size*size;
+    vec2 p=position+offset;
+    quad_coord=vec2(0.0,0.0);
+    if(quad){
+        //Each instance is a quad the size of the point it replaces.
+        quad_coord=CORNERS[gl_VertexID];
+        p+=(quad_coord-vec2(0.5,0.5))*gl_PointSize/point_mul;
+    }
+    vec3 pp=vec3(p,1.0);
+    pos=position*0.005;
+    edge_dist=edge;
+    vcol=color;
+    ps=gl_PointSize;
     gl_Position = vec4(mmatrix*pp.xyz, 1.0);
 }";
 
@@ -43,36 +234,261 @@ pub static CIRCLE_FS_SRC: &'static str = "
 #version 300 es
 precision mediump float;
 uniform vec4 bcol;
+uniform bool antialias;
+uniform float inner;
+uniform vec2 axis_scale;
 out vec4 out_color;
 in vec2 pos;
 in float ps;
+in vec2 quad_coord;
+uniform bool quad;
+const int MAX_STOPS=8;
+uniform int gradient;
+uniform highp vec2 gradient_start;
+uniform highp vec2 gradient_end;
+uniform int stop_count;
+uniform float stop_offsets[MAX_STOPS];
+uniform vec4 stop_colors[MAX_STOPS];
+in highp vec2 world_pos;
+in highp float point_extent;
+
+//The color of the gradient at a point in the same coordinates as the vertices.
+//A linear gradient goes from 0 at the start to 1 at the end.
+//A radial gradient goes from 0 at the start to 1 one radius away, which is stored in gradient_end.x.
+vec4 gradient_color(highp vec2 p){
+    highp float t;
+    if(gradient==1){
+        highp vec2 d=gradient_end-gradient_start;
+        t=dot(p-gradient_start,d)/max(dot(d,d),1e-12);
+    }else{
+        t=length(p-gradient_start)/max(gradient_end.x,1e-6);
+    }
+    t=clamp(t,0.0,1.0);
+    vec4 col=stop_colors[0];
+    for(int i=1;i<MAX_STOPS;i++){
+        if(i>=stop_count){
+            break;
+        }
+        float a=stop_offsets[i-1];
+        float b=stop_offsets[i];
+        if(t>=a){
+            col=mix(stop_colors[i-1],stop_colors[i],clamp((t-a)/max(b-a,0.00001),0.0,1.0));
+        }
+    }
+    return col;
+}
 
 void main() {
+    vec2 point_coord = quad ? quad_coord : gl_PointCoord;
 
-    vec2 coord = gl_PointCoord - vec2(0.5,0.5);
-    float dis=dot(coord,coord);
-    if(dis > 0.25){                  //outside of circle radius?
-        discard;
+    vec4 col=bcol;
+    if(gradient!=0){
+        col=gradient_color(world_pos+(point_coord-vec2(0.5,0.5))*point_extent);
     }
 
-    out_color = bcol;
+    //0 at the center and 1 on the edge of the circle or ellipse.
+    vec2 coord = (point_coord - vec2(0.5,0.5))/axis_scale;
+    float dis=length(coord)*2.0;
+
+    if(antialias){
+        //Distance to the edge of the circle in pixels.
+        //Fade out over one pixel centered on the edge.
+        float rad=ps*0.5;
+        float coverage=clamp((1.0-dis)*rad+0.5,0.0,1.0);
+        if(inner > 0.0){
+            coverage*=clamp((dis-inner)*rad+0.5,0.0,1.0);
+        }
+        if(coverage <= 0.0){
+            discard;
+        }
+        out_color = vec4(col.rgb,col.a*coverage);
+    }else{
+        if(dis > 1.0 || dis < inner){     //outside of circle radius or inside the hole of a ring?
+            discard;
+        }
+        out_color = col;
+    }
 }";
 
 pub static REGULAR_FS_SRC: &'static str = "
 #version 300 es
 precision mediump float;
 uniform vec4 bcol;
+uniform bool antialias;
 in vec2 pos;
+in vec4 edge_dist;
+in vec2 quad_coord;
+uniform bool quad;
 out vec4 out_color;
+const int MAX_STOPS=8;
+uniform int gradient;
+uniform highp vec2 gradient_start;
+uniform highp vec2 gradient_end;
+uniform int stop_count;
+uniform float stop_offsets[MAX_STOPS];
+uniform vec4 stop_colors[MAX_STOPS];
+in highp vec2 world_pos;
+in highp float point_extent;
+
+//The color of the gradient at a point in the same coordinates as the vertices.
+//A linear gradient goes from 0 at the start to 1 at the end.
+//A radial gradient goes from 0 at the start to 1 one radius away, which is stored in gradient_end.x.
+vec4 gradient_color(highp vec2 p){
+    highp float t;
+    if(gradient==1){
+        highp vec2 d=gradient_end-gradient_start;
+        t=dot(p-gradient_start,d)/max(dot(d,d),1e-12);
+    }else{
+        t=length(p-gradient_start)/max(gradient_end.x,1e-6);
+    }
+    t=clamp(t,0.0,1.0);
+    vec4 col=stop_colors[0];
+    for(int i=1;i<MAX_STOPS;i++){
+        if(i>=stop_count){
+            break;
+        }
+        float a=stop_offsets[i-1];
+        float b=stop_offsets[i];
+        if(t>=a){
+            col=mix(stop_colors[i-1],stop_colors[i],clamp((t-a)/max(b-a,0.00001),0.0,1.0));
+        }
+    }
+    return col;
+}
 
 void main() {
     out_color=bcol;
+    if(gradient!=0){
+        highp vec2 p=world_pos;
+        if(point_extent>0.0){
+            //Squares are drawn as points, so find where in the square we are.
+            p+=((quad ? quad_coord : gl_PointCoord)-vec2(0.5,0.5))*point_extent;
+        }
+        out_color=gradient_color(p);
+    }
+    if(antialias){
+        //Each component is zero on an edge. Fade out over about a pixel
+        //as we approach the closest one.
+        vec4 d=edge_dist/max(fwidth(edge_dist),vec4(0.0001));
+        out_color.a*=clamp(min(min(d.x,d.y),min(d.z,d.w)),0.0,1.0);
+    }
+}";
+
+///The per vertex color is multiplied with the uniform color,
+///so `with_color` can still be used to tint or fade out the whole batch.
+pub static COLORED_CIRCLE_FS_SRC: &'static str = "
+#version 300 es
+precision mediump float;
+uniform vec4 bcol;
+uniform bool antialias;
+uniform float inner;
+uniform vec2 axis_scale;
+out vec4 out_color;
+in vec2 pos;
+in vec4 vcol;
+in float ps;
+in vec2 quad_coord;
+uniform bool quad;
+
+void main() {
+    vec4 col=bcol*vcol;
+
+    vec2 coord = ((quad ? quad_coord : gl_PointCoord) - vec2(0.5,0.5))/axis_scale;
+    float dis=length(coord)*2.0;
+
+    if(antialias){
+        float rad=ps*0.5;
+        float coverage=clamp((1.0-dis)*rad+0.5,0.0,1.0);
+        if(inner > 0.0){
+            coverage*=clamp((dis-inner)*rad+0.5,0.0,1.0);
+        }
+        if(coverage <= 0.0){
+            discard;
+        }
+        out_color = vec4(col.rgb,col.a*coverage);
+    }else{
+        if(dis > 1.0 || dis < inner){
+            discard;
+        }
+        out_color = col;
+    }
+}";
+
+pub static COLORED_REGULAR_FS_SRC: &'static str = "
+#version 300 es
+precision mediump float;
+uniform vec4 bcol;
+uniform bool antialias;
+in vec2 pos;
+in vec4 edge_dist;
+in vec4 vcol;
+out vec4 out_color;
+
+void main() {
+    out_color=bcol*vcol;
+    if(antialias){
+        vec4 d=edge_dist/max(fwidth(edge_dist),vec4(0.0001));
+        out_color.a*=clamp(min(min(d.x,d.y),min(d.z,d.w)),0.0,1.0);
+    }
 }";
 
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug, Default)]
 pub struct Vertex(pub [f32; 2]);
 
+///A vertex that also knows how far it is from the edges of the shape it belongs to.
+///Each edge component is 0 on one of the edges of the shape and 255 on the far side of it.
+///Components that do not correspond to an edge are left at 255.
+///This is what lets lines, arrows and rects fade out their edges when anti-aliasing.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct EdgeVertex {
+    pub pos: [f32; 2],
+    pub edge: [u8; 4],
+}
+
+///A point with its own radius.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SizedVertex {
+    pub pos: [f32; 2],
+    pub radius: f32,
+}
+
+///A vertex that also knows where it is within the shape it belongs to,
+///from 0 to 1 across the bounds of the shape on each axis.
+///This is what lets textures be stretched over shapes other than rects.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct UvVertex {
+    pub pos: [f32; 2],
+    pub uv: [f32; 2],
+}
+
+///A vertex with its own packed RGBA8 color.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ColoredVertex {
+    pub pos: [f32; 2],
+    pub color: [u8; 4],
+}
+
+///An `EdgeVertex` with its own packed RGBA8 color.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ColoredEdgeVertex {
+    pub pos: [f32; 2],
+    pub edge: [u8; 4],
+    pub color: [u8; 4],
+}
+
+///Pack a color into one byte per channel, clamping each channel to [0,1].
+#[inline(always)]
+pub fn pack_color(color: [f32; 4]) -> [u8; 4] {
+    let f = |a: f32| (a.max(0.0).min(1.0) * 255.0 + 0.5) as u8;
+    [f(color[0]), f(color[1]), f(color[2]), f(color[3])]
+}
+
 #[derive(Debug)]
 pub struct CircleProgram {
     pub program: GLuint,
@@ -80,7 +496,21 @@ pub struct CircleProgram {
     pub offset_uniform: GLint,
     pub point_size_uniform: GLint,
     pub bcol_uniform: GLint,
+    pub antialias_uniform: GLint,
+    pub inner_uniform: GLint,
+    pub axis_scale_uniform: GLint,
+    pub quad_uniform: GLint,
+    pub point_mul_uniform: GLint,
+    pub gradient_uniform: GLint,
+    pub gradient_start_uniform: GLint,
+    pub gradient_end_uniform: GLint,
+    pub stop_count_uniform: GLint,
+    pub stop_offsets_uniform: GLint,
+    pub stop_colors_uniform: GLint,
     pub pos_attr: GLint,
+    pub edge_attr: GLint,
+    pub color_attr: GLint,
+    pub size_attr: GLint,
 }
 
 #[derive(Debug)]
@@ -102,6 +532,8 @@ impl CircleProgram {
 
         let matrix = [[scalex, 0.0, 0.0], [0.0, -scaley, 0.0], [tx, ty, 1.0]];
 
+        let point_mul = window_dim.width as f32 / game_width;
+
         unsafe {
             gl::UseProgram(self.program);
             gl_ok!();
@@ -112,9 +544,11 @@ impl CircleProgram {
                 std::mem::transmute(&matrix[0][0]),
             );
             gl_ok!();
+            gl::Uniform1f(self.point_mul_uniform, point_mul);
+            gl_ok!();
         }
 
-        PointMul(window_dim.width as f32 / game_width)
+        PointMul(point_mul)
     }
 
     pub(crate) fn set_buffer_and_draw(
@@ -129,7 +563,6 @@ impl CircleProgram {
         //let square=un.rect;
         let buffer_id = buffer_info.id;
         let offset = common.offset;
-        let length = buffer_info.length;
         let stride = un.stride;
 
         unsafe {
@@ -145,6 +578,52 @@ impl CircleProgram {
             gl::Uniform4fv(self.bcol_uniform, 1, col.as_ptr() as *const _);
             gl_ok!();
 
+            gl::Uniform1i(self.antialias_uniform, un.antialias as i32);
+            gl_ok!();
+
+            gl::Uniform1f(self.inner_uniform, un.inner);
+            gl_ok!();
+
+            gl::Uniform2f(self.axis_scale_uniform, un.axis_scale[0], un.axis_scale[1]);
+            gl_ok!();
+
+            gl::Uniform1i(self.quad_uniform, un.quad as i32);
+            gl_ok!();
+
+            match &un.gradient {
+                Some(g) => {
+                    gl::Uniform1i(self.gradient_uniform, if g.radial { 2 } else { 1 });
+                    gl_ok!();
+
+                    gl::Uniform2f(self.gradient_start_uniform, g.start[0], g.start[1]);
+                    gl_ok!();
+
+                    gl::Uniform2f(self.gradient_end_uniform, g.end[0], g.end[1]);
+                    gl_ok!();
+
+                    gl::Uniform1i(self.stop_count_uniform, g.count as i32);
+                    gl_ok!();
+
+                    gl::Uniform1fv(
+                        self.stop_offsets_uniform,
+                        MAX_GRADIENT_STOPS as i32,
+                        g.offsets.as_ptr(),
+                    );
+                    gl_ok!();
+
+                    gl::Uniform4fv(
+                        self.stop_colors_uniform,
+                        MAX_GRADIENT_STOPS as i32,
+                        g.colors.as_ptr() as *const _,
+                    );
+                    gl_ok!();
+                }
+                None => {
+                    gl::Uniform1i(self.gradient_uniform, 0);
+                    gl_ok!();
+                }
+            }
+
             gl::BindBuffer(gl::ARRAY_BUFFER, buffer_id);
             gl_ok!();
 
@@ -161,22 +640,55 @@ impl CircleProgram {
             );
             gl_ok!();
 
-            gl::DrawArrays(mode, 0 as i32, length as i32);
-
-            gl_ok!();
+            set_edge_attr(self.edge_attr, un);
+            set_color_attr(self.color_attr, un);
+            set_size_attr(self.size_attr, un);
+
+            if un.quad {
+                let attrs = [self.pos_attr, self.edge_attr, self.color_attr, self.size_attr];
+                vbo::set_divisors(&attrs, 1);
+                vbo::draw_point_quads(buffer_info);
+                vbo::set_divisors(&attrs, 0);
+            } else {
+                vbo::draw(mode, buffer_info);
+            }
 
             gl::DisableVertexAttribArray(self.pos_attr as GLuint);
             gl_ok!();
 
+            if un.edges && self.edge_attr >= 0 {
+                gl::DisableVertexAttribArray(self.edge_attr as GLuint);
+                gl_ok!();
+            }
+
+            if un.colors && self.color_attr >= 0 {
+                gl::DisableVertexAttribArray(self.color_attr as GLuint);
+                gl_ok!();
+            }
+
+            if un.sizes && self.size_attr >= 0 {
+                gl::DisableVertexAttribArray(self.size_attr as GLuint);
+                gl_ok!();
+            }
+
             gl::BindBuffer(gl::ARRAY_BUFFER, 0);
             gl_ok!();
         }
     }
 
     pub fn new(frag: &str) -> CircleProgram {
+        Self::with_vertex_shader(VS_SRC, frag)
+    }
+
+    ///A program whose vertices each carry their own color.
+    pub fn new_colored(frag: &str) -> CircleProgram {
+        Self::with_vertex_shader(COLORED_VS_SRC, frag)
+    }
+
+    fn with_vertex_shader(vert: &str, frag: &str) -> CircleProgram {
         unsafe {
             // Create GLSL shaders
-            let vs = compile_shader(VS_SRC, gl::VERTEX_SHADER);
+            let vs = compile_shader(vert, gl::VERTEX_SHADER);
             gl_ok!();
 
             let fs = compile_shader(frag, gl::FRAGMENT_SHADER);
@@ -214,23 +726,208 @@ impl CircleProgram {
                 gl::GetUniformLocation(program, temp.as_ptr());
             gl_ok!();
 
+            let temp=CString::new("antialias").unwrap();
+            let antialias_uniform: GLint =
+                gl::GetUniformLocation(program, temp.as_ptr());
+            gl_ok!();
+
+            let temp=CString::new("inner").unwrap();
+            let inner_uniform: GLint =
+                gl::GetUniformLocation(program, temp.as_ptr());
+            gl_ok!();
+
+            let temp=CString::new("axis_scale").unwrap();
+            let axis_scale_uniform: GLint =
+                gl::GetUniformLocation(program, temp.as_ptr());
+            gl_ok!();
+
+            let temp=CString::new("quad").unwrap();
+            let quad_uniform: GLint =
+                gl::GetUniformLocation(program, temp.as_ptr());
+            gl_ok!();
+
+            let temp=CString::new("point_mul").unwrap();
+            let point_mul_uniform: GLint =
+                gl::GetUniformLocation(program, temp.as_ptr());
+            gl_ok!();
+
+            //These are -1 for the programs that do not support gradients.
+            let temp=CString::new("gradient").unwrap();
+            let gradient_uniform: GLint =
+                gl::GetUniformLocation(program, temp.as_ptr());
+            gl_ok!();
+
+            let temp=CString::new("gradient_start").unwrap();
+            let gradient_start_uniform: GLint =
+                gl::GetUniformLocation(program, temp.as_ptr());
+            gl_ok!();
+
+            let temp=CString::new("gradient_end").unwrap();
+            let gradient_end_uniform: GLint =
+                gl::GetUniformLocation(program, temp.as_ptr());
+            gl_ok!();
+
+            let temp=CString::new("stop_count").unwrap();
+            let stop_count_uniform: GLint =
+                gl::GetUniformLocation(program, temp.as_ptr());
+            gl_ok!();
+
+            let temp=CString::new("stop_offsets").unwrap();
+            let stop_offsets_uniform: GLint =
+                gl::GetUniformLocation(program, temp.as_ptr());
+            gl_ok!();
+
+            let temp=CString::new("stop_colors").unwrap();
+            let stop_colors_uniform: GLint =
+                gl::GetUniformLocation(program, temp.as_ptr());
+            gl_ok!();
+
             let temp=CString::new("position").unwrap();
             let pos_attr =
                 gl::GetAttribLocation(program, temp.as_ptr());
             gl_ok!();
 
+            //This may be -1 if the fragment shader does not use the edge distances.
+            let temp=CString::new("edge").unwrap();
+            let edge_attr =
+                gl::GetAttribLocation(program, temp.as_ptr());
+            gl_ok!();
+
+            //This is -1 for the programs without per vertex colors.
+            let temp=CString::new("color").unwrap();
+            let color_attr =
+                gl::GetAttribLocation(program, temp.as_ptr());
+            gl_ok!();
+
+            let temp=CString::new("size").unwrap();
+            let size_attr =
+                gl::GetAttribLocation(program, temp.as_ptr());
+            gl_ok!();
+
             CircleProgram {
                 program,
                 offset_uniform,
                 point_size_uniform,
                 matrix_uniform,
                 bcol_uniform,
+                antialias_uniform,
+                inner_uniform,
+                axis_scale_uniform,
+                quad_uniform,
+                point_mul_uniform,
+                gradient_uniform,
+                gradient_start_uniform,
+                gradient_end_uniform,
+                stop_count_uniform,
+                stop_offsets_uniform,
+                stop_colors_uniform,
                 pos_attr,
+                edge_attr,
+                color_attr,
+                size_attr,
             }
         }
     }
 }
 
+///Point the edge attribute at the buffer if it has edge distances.
+///Otherwise every fragment is treated as being far away from any edge.
+pub(crate) unsafe fn set_edge_attr(edge_attr: GLint, un: &ProgramUniformValues) {
+    if edge_attr < 0 {
+        return;
+    }
+    if un.edges {
+        gl::EnableVertexAttribArray(edge_attr as GLuint);
+        gl_ok!();
+
+        gl::VertexAttribPointer(
+            edge_attr as GLuint,
+            4,
+            gl::UNSIGNED_BYTE,
+            gl::TRUE,
+            un.stride,
+            (4 * 2) as *const _,
+        );
+        gl_ok!();
+    } else {
+        gl::VertexAttrib4f(edge_attr as GLuint, 1.0, 1.0, 1.0, 1.0);
+        gl_ok!();
+    }
+}
+
+///Point the color attribute at the last four bytes of each vertex if the buffer has colors.
+pub(crate) unsafe fn set_color_attr(color_attr: GLint, un: &ProgramUniformValues) {
+    if color_attr < 0 {
+        return;
+    }
+    if un.colors {
+        gl::EnableVertexAttribArray(color_attr as GLuint);
+        gl_ok!();
+
+        gl::VertexAttribPointer(
+            color_attr as GLuint,
+            4,
+            gl::UNSIGNED_BYTE,
+            gl::TRUE,
+            un.stride,
+            (un.stride - 4) as usize as *const _,
+        );
+        gl_ok!();
+    } else {
+        gl::VertexAttrib4f(color_attr as GLuint, 1.0, 1.0, 1.0, 1.0);
+        gl_ok!();
+    }
+}
+
+///Point the size attribute at the radius of each vertex if the buffer has them.
+///Otherwise every point is the size of the point size uniform.
+pub(crate) unsafe fn set_size_attr(size_attr: GLint, un: &ProgramUniformValues) {
+    if size_attr < 0 {
+        return;
+    }
+    if un.sizes {
+        gl::EnableVertexAttribArray(size_attr as GLuint);
+        gl_ok!();
+
+        gl::VertexAttribPointer(
+            size_attr as GLuint,
+            1,
+            gl::FLOAT,
+            gl::FALSE as GLboolean,
+            un.stride,
+            (4 * 2) as *const _,
+        );
+        gl_ok!();
+    } else {
+        gl::VertexAttrib1f(size_attr as GLuint, 1.0);
+        gl_ok!();
+    }
+}
+
+///Point the uv attribute just past the position of each vertex if the buffer has uvs.
+pub(crate) unsafe fn set_uv_attr(uv_attr: GLint, un: &ProgramUniformValues) {
+    if uv_attr < 0 {
+        return;
+    }
+    if un.uvs {
+        gl::EnableVertexAttribArray(uv_attr as GLuint);
+        gl_ok!();
+
+        gl::VertexAttribPointer(
+            uv_attr as GLuint,
+            2,
+            gl::FLOAT,
+            gl::FALSE as GLboolean,
+            un.stride,
+            (4 * 2) as *const _,
+        );
+        gl_ok!();
+    } else {
+        gl::VertexAttrib2f(uv_attr as GLuint, 0.0, 0.0);
+        gl_ok!();
+    }
+}
+
 impl Drop for CircleProgram {
     fn drop(&mut self) {
         // Cleanup
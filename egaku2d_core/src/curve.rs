@@ -0,0 +1,57 @@
+//! Flattening of bezier curves into line segments.
+//!
+//! The number of segments is picked from a bound on how far the curve can stray
+//! from its chords, so flat curves get few segments and tight curves get many.
+
+type P = [f32; 2];
+
+const MAX_SEGMENTS: usize = 1024;
+
+#[inline(always)]
+fn len(a: P) -> f32 {
+    (a[0] * a[0] + a[1] * a[1]).sqrt()
+}
+
+///Number of equal parameter steps needed for the chords to stay within `tolerance` of the curve,
+///given the largest second difference of the control points.
+fn segments(second_diff: f32, tolerance: f32) -> usize {
+    let n = (second_diff / tolerance.max(core::f32::EPSILON)).sqrt().ceil();
+    if n.is_finite() {
+        (n as usize).max(1).min(MAX_SEGMENTS)
+    } else {
+        MAX_SEGMENTS
+    }
+}
+
+///Push the points of a quadratic curve onto `out`, not including the first point.
+pub fn flatten_quad(p0: P, c: P, p1: P, tolerance: f32, out: &mut Vec<P>) {
+    //The chord of a step h strays at most |p0-2c+p1|*h^2/4 from the curve.
+    let dd = len([p0[0] - 2.0 * c[0] + p1[0], p0[1] - 2.0 * c[1] + p1[1]]);
+    let n = segments(dd / 4.0, tolerance);
+    for i in 1..=n {
+        let t = i as f32 / n as f32;
+        let u = 1.0 - t;
+        let (a, b, d) = (u * u, 2.0 * u * t, t * t);
+        out.push([
+            a * p0[0] + b * c[0] + d * p1[0],
+            a * p0[1] + b * c[1] + d * p1[1],
+        ]);
+    }
+}
+
+///Push the points of a cubic curve onto `out`, not including the first point.
+pub fn flatten_cubic(p0: P, c0: P, c1: P, p1: P, tolerance: f32, out: &mut Vec<P>) {
+    //The chord of a step h strays at most 3/4*max(|p0-2c0+c1|,|c0-2c1+p1|)*h^2 from the curve.
+    let d0 = len([p0[0] - 2.0 * c0[0] + c1[0], p0[1] - 2.0 * c0[1] + c1[1]]);
+    let d1 = len([c0[0] - 2.0 * c1[0] + p1[0], c0[1] - 2.0 * c1[1] + p1[1]]);
+    let n = segments(d0.max(d1) * 0.75, tolerance);
+    for i in 1..=n {
+        let t = i as f32 / n as f32;
+        let u = 1.0 - t;
+        let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+        out.push([
+            a * p0[0] + b * c0[0] + c * c1[0] + d * p1[0],
+            a * p0[1] + b * c0[1] + c * c1[1] + d * p1[1],
+        ]);
+    }
+}
@@ -47,6 +47,10 @@ mod sprite_program;
 
 pub mod batch;
 
+///Contains the sprite animation code.
+///The api is described in the crate documentation.
+pub mod animation;
+
 mod textured_shape_program;
 
 ///All the opengl functions generated from the gl_generator crate.
@@ -58,6 +62,14 @@ pub mod gl {
 ///They all follow the same api outlined in the crate documentation.
 pub mod shapes;
 
+mod triangulate;
+
+mod stroke;
+
+mod curve;
+
+mod svg;
+
 use self::uniforms::UniformCommon;
 use self::uniforms::*;
 
@@ -144,7 +156,119 @@ pub mod uniforms {
             self
         }
 
+        ///Map the texture set with `with_texture` with a full 2d affine transform
+        ///instead of with its scale and offset. This allows non-uniform scaling, rotation and shear.
+        ///The rows `[[a, b, tx], [c, d, ty]]` take a position `(x, y)` to the texture pixel
+        ///`(a*x + b*y + tx, c*x + d*y + ty)`. Positions are in the coordinates given by `with_texture_mode`.
+        pub fn with_texture_transform(&mut self, transform: [[f32; 3]; 2]) -> &mut Self {
+            match &mut self.un {
+                UniformVals::Sprite(_) => {}
+                UniformVals::Regular(s) => {
+                    s.texture_transform = Some(transform);
+                }
+                UniformVals::Circle(s) => {
+                    s.texture_transform = Some(transform);
+                }
+            }
+            self
+        }
+
+        ///Choose what the texture set with `with_texture` is pinned to.
+        ///The default is `TextureSpace::Screen`.
+        ///In `TextureSpace::World` one texture pixel covers one unit at a scale of 1,
+        ///and the offset passed to `with_texture` is in the same units.
+        ///In `TextureSpace::Local` the offset is in texture pixels.
+        pub fn with_texture_mode(&mut self, space: sprite::TextureSpace) -> &mut Self {
+            match &mut self.un {
+                UniformVals::Sprite(_) => {}
+                UniformVals::Regular(s) => {
+                    s.texture_space = space;
+                }
+                UniformVals::Circle(s) => {
+                    s.texture_space = space;
+                }
+            }
+            self
+        }
+
+        ///Smooth out the edges of circles, lines, arrows and rects by fading them out over about a pixel.
+        ///Other shapes are drawn as normal.
+        pub fn with_antialiasing(&mut self, antialias: bool) -> &mut Self {
+            match &mut self.un {
+                UniformVals::Sprite(_) => {}
+                UniformVals::Regular(s) => {
+                    s.antialias = antialias;
+                }
+                UniformVals::Circle(s) => {
+                    s.antialias = antialias;
+                }
+            }
+            self
+        }
+
+        ///Fill with a linear gradient that goes from `c0` at `p0` to `c1` at `p1`
+        ///instead of with a flat color.
+        ///See `with_linear_gradient_stops`.
+        pub fn with_linear_gradient(
+            &mut self,
+            p0: [f32; 2],
+            c0: [f32; 4],
+            p1: [f32; 2],
+            c1: [f32; 4],
+        ) -> &mut Self {
+            self.with_linear_gradient_stops(p0, p1, &[(0.0, c0), (1.0, c1)])
+        }
+
+        ///Fill with a linear gradient from `p0` to `p1` instead of with a flat color.
+        ///Each stop is an offset between 0 (at `p0`) and 1 (at `p1`) and the color there.
+        ///Up to eight stops are used.
+        ///The points are in the same coordinates as the vertices, so the gradient moves along with `with_offset`.
+        ///Works for circles, squares, lines, rects, polygons and the other untextured shapes.
+        ///Sprites and shapes that are textured or have per vertex colors ignore it.
+        pub fn with_linear_gradient_stops(
+            &mut self,
+            p0: [f32; 2],
+            p1: [f32; 2],
+            stops: &[(f32, [f32; 4])],
+        ) -> &mut Self {
+            self.set_gradient(Gradient::new(false, p0, p1, stops))
+        }
+
+        ///Fill with a radial gradient instead of with a flat color.
+        ///Each stop is an offset between 0 (at `center`) and 1 (`radius` away from it) and the color there.
+        ///Otherwise the same as `with_linear_gradient_stops`.
+        pub fn with_radial_gradient(
+            &mut self,
+            center: [f32; 2],
+            radius: f32,
+            stops: &[(f32, [f32; 4])],
+        ) -> &mut Self {
+            self.set_gradient(Gradient::new(true, center, [radius, 0.0], stops))
+        }
+
+        fn set_gradient(&mut self, gradient: Option<Gradient>) -> &mut Self {
+            match &mut self.un {
+                UniformVals::Sprite(_) => {}
+                UniformVals::Regular(s) => {
+                    s.gradient = gradient;
+                }
+                UniformVals::Circle(s) => {
+                    s.gradient = gradient;
+                }
+            }
+            self
+        }
+
         pub fn draw(&mut self) {
+            //Points bigger than the driver can draw are drawn as quads instead.
+            let max_point_size = self.sys.max_point_size;
+            match &mut self.un {
+                UniformVals::Sprite(a) => a.quad = a.radius * a.max_scale > max_point_size,
+                UniformVals::Regular(a) | UniformVals::Circle(a) => {
+                    a.quad = a.mode == gl::POINTS && a.radius * a.max_size > max_point_size
+                }
+            }
+
             match &self.un {
                 UniformVals::Sprite(a) => {
                     self.sys
@@ -152,7 +276,11 @@ pub mod uniforms {
                         .set_buffer_and_draw(&self.common, a, self.buffer);
                 }
                 UniformVals::Regular(a) => {
-                    if a.texture.is_some() {
+                    if a.colors {
+                        self.sys
+                            .colored_regular_program
+                            .set_buffer_and_draw(&self.common, a, self.buffer);
+                    } else if a.texture.is_some() {
                         self.sys.textured_shape_program.set_buffer_and_draw(
                             &self.common,
                             a,
@@ -165,7 +293,11 @@ pub mod uniforms {
                     }
                 }
                 UniformVals::Circle(a) => {
-                    if a.texture.is_some() {
+                    if a.colors {
+                        self.sys
+                            .colored_circle_program
+                            .set_buffer_and_draw(&self.common, a, self.buffer);
+                    } else if a.texture.is_some() {
                         self.sys.textured_circle_program.set_buffer_and_draw(
                             &self.common,
                             a,
@@ -201,6 +333,8 @@ pub struct SimpleCanvas {
     _ns: NotSend,
     circle_program: CircleProgram,
     regular_program: CircleProgram,
+    colored_circle_program: CircleProgram,
+    colored_regular_program: CircleProgram,
     sprite_program: SpriteProgram,
     textured_shape_program: textured_shape_program::TexturedShapeProgram,
     textured_circle_program: textured_shape_program::TexturedShapeProgram,
@@ -211,9 +345,16 @@ pub struct SimpleCanvas {
     //this allows us to not have to implement Drop for the session to make sure that the buffer is cleared.
     //if they were to implement drop, they would be slightly less egronomic to use.
     circle_buffer: vbo::GrowableBuffer<circle_program::Vertex>,
+    edge_buffer: vbo::GrowableBuffer<circle_program::EdgeVertex>,
+    colored_buffer: vbo::GrowableBuffer<circle_program::ColoredVertex>,
+    sized_buffer: vbo::GrowableBuffer<circle_program::SizedVertex>,
+    uv_buffer: vbo::GrowableBuffer<circle_program::UvVertex>,
+    colored_edge_buffer: vbo::GrowableBuffer<circle_program::ColoredEdgeVertex>,
+    index_buffer: vbo::GrowableIndexBuffer,
     sprite_buffer: vbo::GrowableBuffer<sprite_program::Vertex>,
     color: [f32; 4], //Default color used
     offset: Vec2<f32>, //Default offset
+    max_point_size: f32, //Largest point size the driver supports
 }
 
 impl SimpleCanvas {
@@ -228,6 +369,8 @@ impl SimpleCanvas {
     pub fn set_viewport(&mut self, window_dim: FixedAspectVec2, game_width: f32) {
         self.point_mul = self.circle_program.set_viewport(window_dim, game_width);
         let _ = self.regular_program.set_viewport(window_dim, game_width);
+        let _ = self.colored_circle_program.set_viewport(window_dim, game_width);
+        let _ = self.colored_regular_program.set_viewport(window_dim, game_width);
         let _ = self.sprite_program.set_viewport(window_dim, game_width);
         let _ = self
             .textured_shape_program
@@ -241,12 +384,24 @@ impl SimpleCanvas {
     //which could make opengl calls simultaneously
     pub unsafe fn new(window_dim: FixedAspectVec2) -> SimpleCanvas {
         let circle_buffer = vbo::GrowableBuffer::new();
+        let edge_buffer = vbo::GrowableBuffer::new();
+        let colored_buffer = vbo::GrowableBuffer::new();
+        let sized_buffer = vbo::GrowableBuffer::new();
+        let uv_buffer = vbo::GrowableBuffer::new();
+        let colored_edge_buffer = vbo::GrowableBuffer::new();
+        let index_buffer = vbo::GrowableIndexBuffer::new();
         let sprite_buffer = vbo::GrowableBuffer::new();
 
         let mut circle_program = CircleProgram::new(circle_program::CIRCLE_FS_SRC);
 
         let mut regular_program = CircleProgram::new(circle_program::REGULAR_FS_SRC);
 
+        let mut colored_circle_program =
+            CircleProgram::new_colored(circle_program::COLORED_CIRCLE_FS_SRC);
+
+        let mut colored_regular_program =
+            CircleProgram::new_colored(circle_program::COLORED_REGULAR_FS_SRC);
+
         let mut textured_shape_program = textured_shape_program::TexturedShapeProgram::new(
             textured_shape_program::REGULAR_FS_SRC,
         );
@@ -258,10 +413,16 @@ impl SimpleCanvas {
 
         let point_mul = circle_program.set_viewport(window_dim, window_dim.width as f32);
         let _ = regular_program.set_viewport(window_dim, window_dim.width as f32);
+        let _ = colored_circle_program.set_viewport(window_dim, window_dim.width as f32);
+        let _ = colored_regular_program.set_viewport(window_dim, window_dim.width as f32);
         let _ = sprite_program.set_viewport(window_dim, window_dim.width as f32);
         let _ = textured_shape_program.set_viewport(window_dim, window_dim.width as f32);
         let _ = textured_circle_program.set_viewport(window_dim, window_dim.width as f32);
 
+        let mut point_size_range = [0.0f32; 2];
+        gl::GetFloatv(gl::ALIASED_POINT_SIZE_RANGE, point_size_range.as_mut_ptr());
+        gl_ok!();
+
         gl::Enable(gl::BLEND);
         gl_ok!();
         gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
@@ -273,36 +434,80 @@ impl SimpleCanvas {
             sprite_program,
             regular_program,
             circle_program,
+            colored_circle_program,
+            colored_regular_program,
             circle_buffer,
+            edge_buffer,
+            colored_buffer,
+            sized_buffer,
+            uv_buffer,
+            colored_edge_buffer,
+            index_buffer,
             sprite_buffer,
             textured_shape_program,
             textured_circle_program,
             color: [1.0; 4],
-            offset: vec2same(0.0)
+            offset: vec2same(0.0),
+            max_point_size: point_size_range[1],
         }
     }
 
     pub fn sprites(&mut self) -> sprite::SpriteSession {
-        sprite::SpriteSession { verts: Vec::new() }
+        sprite::SpriteSession::new()
     }
 
     pub fn circles(&mut self) -> CircleSession {
         CircleSession { verts: Vec::new() }
     }
 
+    ///Circles that each have their own color.
+    pub fn colored_circles(&mut self) -> ColoredCircleSession {
+        ColoredCircleSession::new()
+    }
+
+    ///Circles that each have their own radius.
+    pub fn sized_circles(&mut self) -> SizedCircleSession {
+        SizedCircleSession::new()
+    }
+
+    pub fn rings(&mut self) -> RingSession {
+        RingSession { verts: Vec::new() }
+    }
+
+    pub fn ellipses(&mut self) -> EllipseSession {
+        EllipseSession { verts: Vec::new() }
+    }
+
     pub fn squares(&mut self) -> SquareSession {
         SquareSession { verts: Vec::new() }
     }
+    ///Squares that each have their own radius.
+    pub fn sized_squares(&mut self) -> SizedSquareSession {
+        SizedSquareSession::new()
+    }
     pub fn rects(&mut self) -> RectSession {
         RectSession { verts: Vec::new() }
     }
+    ///Rects that each have their own color.
+    pub fn colored_rects(&mut self) -> ColoredRectSession {
+        ColoredRectSession::new()
+    }
+    pub fn regular_polygons(&mut self) -> RegularPolygonSession {
+        RegularPolygonSession { verts: Vec::new() }
+    }
+    pub fn triangles(&mut self) -> TriangleSession {
+        TriangleSession { verts: Vec::new() }
+    }
+
+    pub fn meshes<I: MeshIndex>(&mut self) -> MeshSession<I> {
+        MeshSession::new()
+    }
+    pub fn polygons(&mut self) -> PolygonSession {
+        PolygonSession { verts: Vec::new() }
+    }
     pub fn arrows(&mut self, radius: f32) -> ArrowSession {
         let kk = self.point_mul.0;
-
-        ArrowSession {
-            radius: radius * kk,
-            verts: Vec::new(),
-        }
+        ArrowSession::new(radius * kk)
     }
 
     pub fn lines(&mut self, radius: f32) -> LineSession {
@@ -313,6 +518,43 @@ impl SimpleCanvas {
         }
     }
 
+    pub fn arcs(&mut self, radius: f32) -> ArcSession {
+        let kk = self.point_mul.0;
+        //Stay within a quarter of a pixel of the true arcs.
+        ArcSession::new(radius * kk, 0.25 / kk)
+    }
+
+    pub fn polylines(&mut self, radius: f32) -> PolylineSession {
+        let kk = self.point_mul.0;
+        //Keep round joins and caps within a quarter of a pixel of true arcs.
+        PolylineSession::new(radius * kk, 0.25 / kk)
+    }
+
+    pub fn curves(&mut self, radius: f32) -> CurveSession {
+        let kk = self.point_mul.0;
+        //Stay within a quarter of a pixel of the true curve.
+        CurveSession::new(radius * kk, 0.25 / kk)
+    }
+
+    ///Build shapes out of lines and curves like the html canvas. See `PathSession`.
+    pub fn paths(&mut self, radius: f32) -> PathSession {
+        let kk = self.point_mul.0;
+        //Stay within a quarter of a pixel of the true curves.
+        PathSession::new(radius * kk, 0.25 / kk)
+    }
+
+    ///Load vector art from svg. See `SvgSession`.
+    pub fn svg(&mut self) -> SvgSession {
+        let kk = self.point_mul.0;
+        SvgSession::new(kk, 0.25 / kk)
+    }
+
+    pub fn rounded_rects(&mut self, corner_radius: f32, radius: f32) -> RoundedRectSession {
+        let kk = self.point_mul.0;
+        //Stay within a quarter of a pixel of the true corners.
+        RoundedRectSession::new(corner_radius, radius * kk, 0.25 / kk)
+    }
+
     pub fn clear_color(&mut self, back_color: [f32; 3]) {
         unsafe {
             gl::ClearColor(back_color[0], back_color[1], back_color[2], 1.0);
@@ -2,6 +2,115 @@ use super::*;
 
 //pub use self::circle_program::Vertex;
 
+pub struct PolygonSave {
+    _ns: NotSend,
+    buffer: vbo::StaticBuffer<circle_program::UvVertex>,
+}
+
+impl PolygonSave {
+    pub fn uniforms<'a>(&'a self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new_with_uvs(0.0, gl::TRIANGLES);
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer: self.buffer.get_info(),
+        }
+    }
+}
+
+///Draws filled simple polygons. They may be concave and they may have holes.
+///Each polygon is triangulated on the cpu as it is added.
+pub struct PolygonSession {
+    pub(crate) verts: Vec<circle_program::UvVertex>,
+}
+
+impl PolygonSession {
+    pub fn new() -> Self {
+        PolygonSession { verts: Vec::new() }
+    }
+
+    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> PolygonSave {
+        PolygonSave {
+            _ns: ns(),
+            buffer: vbo::StaticBuffer::new(&self.verts),
+        }
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        self.verts.append(&mut other.verts);
+    }
+
+    pub fn send_and_uniforms<'a>(&'a mut self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        sys.uv_buffer.send_to_gpu(&self.verts);
+
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new_with_uvs(0.0, gl::TRIANGLES);
+        let buffer = sys.uv_buffer.get_info(self.verts.len());
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer,
+        }
+    }
+
+    ///Add a simple polygon. The outline may be concave and may be wound in either direction.
+    ///The outline is implicitly closed, so the first point does not need to be repeated.
+    pub fn add(&mut self, outline: &[PointType]) -> &mut Self {
+        self.add_with_holes(outline, &[])
+    }
+
+    ///Add a simple polygon with holes cut out of it.
+    ///The holes must lie inside of the outline and must not overlap each other.
+    pub fn add_with_holes(&mut self, outline: &[PointType], holes: &[&[PointType]]) -> &mut Self {
+        let mut verts = Vec::new();
+        triangulate::triangulate(outline, holes, &mut verts);
+        push_with_uvs(&verts, bounds(outline), &mut self.verts);
+        self
+    }
+}
+
+///The smallest rect around the points, in the same format as the rect session.
+fn bounds(points: &[PointType]) -> [f32; 4] {
+    let (mut min, mut max) = ([f32::INFINITY; 2], [f32::NEG_INFINITY; 2]);
+    for p in points.iter() {
+        min = [min[0].min(p[0]), min[1].min(p[1])];
+        max = [max[0].max(p[0]), max[1].max(p[1])];
+    }
+    [min[0], max[0], min[1], max[1]]
+}
+
+///Add the vertices along with where they are within the rect, for textures in `TextureSpace::Local`.
+///An axis the rect has no length along maps to 0.
+fn push_with_uvs(
+    verts: &[circle_program::Vertex],
+    rect: [f32; 4],
+    out: &mut Vec<circle_program::UvVertex>,
+) {
+    let along = |a: f32, start: f32, end: f32| {
+        if end > start {
+            (a - start) / (end - start)
+        } else {
+            0.0
+        }
+    };
+    out.extend(verts.iter().map(|v| circle_program::UvVertex {
+        pos: v.0,
+        uv: [
+            along(v.0[0], rect[0], rect[1]),
+            along(v.0[1], rect[2], rect[3]),
+        ],
+    }));
+}
+
 pub struct SquareSave {
     _ns: NotSend,
     buffer: vbo::StaticBuffer<circle_program::Vertex>,
@@ -138,39 +247,42 @@ impl CircleSession {
     }
 }
 
-pub struct RectSave {
+pub struct ColoredCircleSave {
     _ns: NotSend,
-    buffer: vbo::StaticBuffer<circle_program::Vertex>,
+    buffer: vbo::StaticBuffer<circle_program::ColoredVertex>,
 }
-
-impl RectSave {
-    pub fn uniforms<'a>(&'a self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+impl ColoredCircleSave {
+    pub fn uniforms<'a>(&'a self, sys: &'a mut SimpleCanvas, radius: f32) -> Uniforms<'a> {
         let common = UniformCommon {
             color: sys.color,
             offset: sys.offset,
         };
-        let un = ProgramUniformValues::new(0.0, gl::TRIANGLES);
+        let un = ProgramUniformValues::new_with_colors(radius, gl::POINTS);
+
         let buffer = self.buffer.get_info();
         Uniforms {
-            sys,
             common,
-            un: UniformVals::Regular(un),
+            sys,
+            un: UniformVals::Circle(un),
             buffer,
         }
     }
 }
 
-pub struct RectSession {
-    pub(crate) verts: Vec<circle_program::Vertex>,
+///Circles that each have their own color, so differently colored circles
+///can be drawn in one draw call.
+///The colors are multiplied with the color set with `with_color`, which defaults to white.
+///Textures are not supported.
+pub struct ColoredCircleSession {
+    pub(crate) verts: Vec<circle_program::ColoredVertex>,
 }
 
-impl RectSession {
+impl ColoredCircleSession {
     pub fn new() -> Self {
-        RectSession { verts: Vec::new() }
+        ColoredCircleSession { verts: Vec::new() }
     }
-
-    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> RectSave {
-        RectSave {
+    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> ColoredCircleSave {
+        ColoredCircleSave {
             _ns: ns(),
             buffer: vbo::StaticBuffer::new(&self.verts),
         }
@@ -179,77 +291,146 @@ impl RectSession {
     pub fn append(&mut self, other: &mut Self) {
         self.verts.append(&mut other.verts);
     }
-    pub fn send_and_uniforms<'a>(&'a mut self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
-        sys.circle_buffer.send_to_gpu(&self.verts);
+    pub fn send_and_uniforms<'a>(
+        &'a mut self,
+        sys: &'a mut SimpleCanvas,
+        radius: f32,
+    ) -> Uniforms<'a> {
+        sys.colored_buffer.send_to_gpu(&self.verts);
 
         let common = UniformCommon {
             color: sys.color,
-            offset: vec2same(0.0),
+            offset: sys.offset,
         };
-        let un = ProgramUniformValues::new(0.0, gl::TRIANGLES);
-        let buffer = sys.circle_buffer.get_info(self.verts.len());
+        let un = ProgramUniformValues::new_with_colors(radius, gl::POINTS);
+
+        let buffer = sys.colored_buffer.get_info(self.verts.len());
         Uniforms {
             sys,
             common,
-            un: UniformVals::Regular(un),
+            un: UniformVals::Circle(un),
             buffer,
         }
     }
 
     #[inline(always)]
-    fn create_rect(rect: [f32; 4]) -> [circle_program::Vertex; 6] {
-        let rect:Rect<f32> = core::convert::From::from(rect);
-        let [tl, tr, br, bl] = rect.get_corners();
-        //let arr = [tr, tl, bl, bl, br, tr];
+    pub fn add(&mut self, point: [f32; 2], color: [f32; 4]) -> &mut Self {
+        self.verts.push(circle_program::ColoredVertex {
+            pos: point,
+            color: circle_program::pack_color(color),
+        });
+        self
+    }
+}
+
+///The gpu side of points that each have their own radius.
+///If they all turned out to have the same radius, they are stored without it.
+enum SizedBuffer {
+    Uniform(vbo::StaticBuffer<circle_program::Vertex>, f32),
+    ///Also keeps the biggest radius, to know when the points need to be drawn as quads.
+    Varying(vbo::StaticBuffer<circle_program::SizedVertex>, f32),
+}
+
+///The radius shared by all of the points, if there is one.
+fn common_radius(verts: &[circle_program::SizedVertex]) -> Option<f32> {
+    let first = verts.first()?.radius;
+    if verts.iter().all(|v| v.radius == first) {
+        Some(first)
+    } else {
+        None
+    }
+}
+
+///The biggest radius of any of the points.
+fn max_radius(verts: &[circle_program::SizedVertex]) -> f32 {
+    verts.iter().fold(0.0, |acc, v| acc.max(v.radius))
+}
+
+fn compact(verts: &[circle_program::SizedVertex]) -> Vec<circle_program::Vertex> {
+    verts.iter().map(|v| circle_program::Vertex(v.pos)).collect()
+}
 
-        fn doop(a: Vec2<f32>) -> circle_program::Vertex {
-            circle_program::Vertex([a.x, a.y])
+impl SizedBuffer {
+    fn new(verts: &[circle_program::SizedVertex]) -> Self {
+        match common_radius(verts) {
+            Some(radius) => SizedBuffer::Uniform(vbo::StaticBuffer::new(&compact(verts)), radius),
+            None => SizedBuffer::Varying(vbo::StaticBuffer::new(verts), max_radius(verts)),
         }
-        [doop(tr), doop(tl), doop(bl), doop(bl), doop(br), doop(tr)]
     }
-    #[inline(always)]
-    pub fn add(&mut self, rect: [f32; 4]) -> &mut Self {
-        let arr = Self::create_rect(rect);
-        self.verts.extend_from_slice(&arr);
-        self
+
+    fn uniform_values<'a>(&self) -> (ProgramUniformValues<'a>, vbo::BufferInfo) {
+        match self {
+            SizedBuffer::Uniform(buffer, radius) => (
+                ProgramUniformValues::new(*radius, gl::POINTS),
+                buffer.get_info(),
+            ),
+            SizedBuffer::Varying(buffer, max_radius) => (
+                ProgramUniformValues::new_with_sizes(gl::POINTS, *max_radius),
+                buffer.get_info(),
+            ),
+        }
     }
 }
 
-pub struct ArrowSave {
+///Send points that each have their own radius to the gpu,
+///falling back to the compact vertices when they all share a radius.
+fn send_sized<'a>(
+    sys: &mut SimpleCanvas,
+    verts: &[circle_program::SizedVertex],
+) -> (ProgramUniformValues<'a>, vbo::BufferInfo) {
+    match common_radius(verts) {
+        Some(radius) => {
+            sys.circle_buffer.send_to_gpu(&compact(verts));
+            (
+                ProgramUniformValues::new(radius, gl::POINTS),
+                sys.circle_buffer.get_info(verts.len()),
+            )
+        }
+        None => {
+            sys.sized_buffer.send_to_gpu(verts);
+            (
+                ProgramUniformValues::new_with_sizes(gl::POINTS, max_radius(verts)),
+                sys.sized_buffer.get_info(verts.len()),
+            )
+        }
+    }
+}
+
+pub struct SizedCircleSave {
     _ns: NotSend,
-    buffer: vbo::StaticBuffer<circle_program::Vertex>,
+    buffer: SizedBuffer,
 }
-impl ArrowSave {
+impl SizedCircleSave {
     pub fn uniforms<'a>(&'a self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
         let common = UniformCommon {
             color: sys.color,
             offset: sys.offset,
         };
-        let un = ProgramUniformValues::new(0.0, gl::TRIANGLES);
+        let (un, buffer) = self.buffer.uniform_values();
         Uniforms {
-            sys,
             common,
-            un: UniformVals::Regular(un),
-            buffer: self.buffer.get_info(),
+            sys,
+            un: UniformVals::Circle(un),
+            buffer,
         }
     }
 }
-pub struct ArrowSession {
-    pub(crate) radius: f32,
-    pub(crate) verts: Vec<circle_program::Vertex>,
+
+///Circles that each have their own radius.
+///The radius is the same as the one passed to `CircleSession::send_and_uniforms`.
+///If every circle has the same radius, the radius is not sent to the gpu per circle.
+pub struct SizedCircleSession {
+    pub(crate) verts: Vec<circle_program::SizedVertex>,
 }
 
-impl ArrowSession {
-    pub fn new(radius: f32) -> Self {
-        ArrowSession {
-            radius,
-            verts: Vec::new(),
-        }
+impl SizedCircleSession {
+    pub fn new() -> Self {
+        SizedCircleSession { verts: Vec::new() }
     }
-    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> ArrowSave {
-        ArrowSave {
+    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> SizedCircleSave {
+        SizedCircleSave {
             _ns: ns(),
-            buffer: vbo::StaticBuffer::new(&self.verts),
+            buffer: SizedBuffer::new(&self.verts),
         }
     }
 
@@ -257,102 +438,140 @@ impl ArrowSession {
         self.verts.append(&mut other.verts);
     }
     pub fn send_and_uniforms<'a>(&'a mut self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
-        sys.circle_buffer.send_to_gpu(&self.verts);
+        let (un, buffer) = send_sized(sys, &self.verts);
 
         let common = UniformCommon {
             color: sys.color,
-            offset: vec2same(0.0),
+            offset: sys.offset,
         };
-        let un = ProgramUniformValues::new(0.0, gl::TRIANGLES);
-        let buffer = sys.circle_buffer.get_info(self.verts.len());
         Uniforms {
             sys,
             common,
-            un: UniformVals::Regular(un),
+            un: UniformVals::Circle(un),
             buffer,
         }
     }
 
     #[inline(always)]
-    fn create_arrow(radius: f32, start: PointType, end: PointType) -> [circle_program::Vertex; 9] {
-        let start = vec2(start[0], start[1]);
-        let end = vec2(end[0], end[1]);
-        let offset = end - start;
-
-        let arrow_head = start + offset * 0.8;
+    pub fn add(&mut self, point: [f32; 2], radius: f32) -> &mut Self {
+        self.verts.push(circle_program::SizedVertex { pos: point, radius });
+        self
+    }
+}
 
-        let k = offset.rotate_90deg_right().normalize_to(1.0);
-        let start1 = start + k * radius;
-        let start2 = start - k * radius;
+pub struct SizedSquareSave {
+    _ns: NotSend,
+    buffer: SizedBuffer,
+}
+impl SizedSquareSave {
+    pub fn uniforms<'a>(&'a self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let (un, buffer) = self.buffer.uniform_values();
+        Uniforms {
+            sys,
+            un: UniformVals::Regular(un),
+            common,
+            buffer,
+        }
+    }
+}
 
-        let end1 = arrow_head + k * radius;
-        let end2 = arrow_head - k * radius;
+///Squares that each have their own radius.
+///The radius is the same as the one passed to `SquareSession::send_and_uniforms`.
+///If every square has the same radius, the radius is not sent to the gpu per square.
+pub struct SizedSquareSession {
+    pub(crate) verts: Vec<circle_program::SizedVertex>,
+}
+impl SizedSquareSession {
+    pub fn new() -> Self {
+        SizedSquareSession { verts: Vec::new() }
+    }
+    #[inline(always)]
+    pub fn add(&mut self, point: [f32; 2], radius: f32) -> &mut Self {
+        self.verts.push(circle_program::SizedVertex { pos: point, radius });
+        self
+    }
 
-        let end11 = arrow_head + k * radius * 2.5;
-        let end22 = arrow_head - k * radius * 2.5;
-        //let arr = [start1, start2, end1, start2, end1, end2, end, end11, end22];
+    pub fn append(&mut self, other: &mut Self) {
+        self.verts.append(&mut other.verts);
+    }
 
-        fn doop(a: Vec2<f32>) -> circle_program::Vertex {
-            circle_program::Vertex([a.x, a.y])
+    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> SizedSquareSave {
+        SizedSquareSave {
+            _ns: ns(),
+            buffer: SizedBuffer::new(&self.verts),
         }
-
-        [
-            doop(start1),
-            doop(start2),
-            doop(end1),
-            doop(start2),
-            doop(end1),
-            doop(end2),
-            doop(end),
-            doop(end11),
-            doop(end22),
-        ]
     }
 
-    #[inline(always)]
-    pub fn add(&mut self, start: PointType, end: PointType) -> &mut Self {
-        let arr = Self::create_arrow(self.radius, start, end);
-        self.verts.extend_from_slice(&arr);
-        self
+    pub fn send_and_uniforms<'a>(&'a mut self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        let (un, buffer) = send_sized(sys, &self.verts);
+
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer,
+        }
     }
 }
 
-pub struct LineSave {
+pub struct RingSave {
     _ns: NotSend,
     buffer: vbo::StaticBuffer<circle_program::Vertex>,
 }
-
-impl LineSave {
-    pub fn uniforms<'a>(&'a self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+impl RingSave {
+    pub fn uniforms<'a>(
+        &'a self,
+        sys: &'a mut SimpleCanvas,
+        radius: f32,
+        width: f32,
+    ) -> Uniforms<'a> {
         let common = UniformCommon {
             color: sys.color,
             offset: sys.offset,
         };
-        let un = ProgramUniformValues::new(0.0, gl::TRIANGLES);
+        let un = RingSession::uniform_values(radius, width);
+
+        let buffer = self.buffer.get_info();
         Uniforms {
-            sys,
             common,
-            un: UniformVals::Regular(un),
-            buffer: self.buffer.get_info(),
+            sys,
+            un: UniformVals::Circle(un),
+            buffer,
         }
     }
 }
 
-pub struct LineSession {
-    pub(crate) radius: f32,
+///Draws rings, or outlined circles.
+///Like circles, each ring is just one point.
+pub struct RingSession {
     pub(crate) verts: Vec<circle_program::Vertex>,
 }
 
-impl LineSession {
-    pub fn new(radius: f32) -> Self {
-        LineSession {
-            radius,
-            verts: Vec::new(),
-        }
+impl RingSession {
+    pub fn new() -> Self {
+        RingSession { verts: Vec::new() }
     }
 
-    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> LineSave {
-        LineSave {
+    fn uniform_values<'a>(radius: f32, width: f32) -> ProgramUniformValues<'a> {
+        let mut un = ProgramUniformValues::new(radius, gl::POINTS);
+        un.inner = if radius > 0.0 {
+            (1.0 - width / radius).max(0.0)
+        } else {
+            0.0
+        };
+        un
+    }
+
+    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> RingSave {
+        RingSave {
             _ns: ns(),
             buffer: vbo::StaticBuffer::new(&self.verts),
         }
@@ -361,57 +580,2180 @@ impl LineSession {
     pub fn append(&mut self, other: &mut Self) {
         self.verts.append(&mut other.verts);
     }
-    pub fn send_and_uniforms<'a>(&'a mut self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+
+    ///The width is the thickness of the ring in the same units as the radius.
+    ///A width as large as the radius draws a filled circle.
+    pub fn send_and_uniforms<'a>(
+        &'a mut self,
+        sys: &'a mut SimpleCanvas,
+        radius: f32,
+        width: f32,
+    ) -> Uniforms<'a> {
         sys.circle_buffer.send_to_gpu(&self.verts);
 
         let common = UniformCommon {
             color: sys.color,
             offset: sys.offset,
         };
+        let un = Self::uniform_values(radius, width);
 
-        let un = ProgramUniformValues::new(0.0, gl::TRIANGLES);
         let buffer = sys.circle_buffer.get_info(self.verts.len());
         Uniforms {
             sys,
             common,
-            un: UniformVals::Regular(un),
+            un: UniformVals::Circle(un),
             buffer,
         }
     }
 
     #[inline(always)]
-    fn create_line(radius: f32, start: PointType, end: PointType) -> [circle_program::Vertex; 6] {
-        let start = vec2(start[0], start[1]); //TODO a program that detected bad uses like this would be cool
-        let end = vec2(end[0], end[1]);
-
-        let offset = end - start;
-        let k = offset.rotate_90deg_right().normalize_to(1.0);
-        let start1 = start + k * radius;
-        let start2 = start - k * radius;
-
-        let end1 = end + k * radius;
-        let end2 = end - k * radius;
+    pub fn add(&mut self, point: [f32; 2]) -> &mut Self {
+        self.verts.push(circle_program::Vertex(point));
+        self
+    }
+}
 
-        //let arr = [start1, start2, end1, start2, end1, end2];
+pub struct EllipseSave {
+    _ns: NotSend,
+    buffer: vbo::StaticBuffer<circle_program::Vertex>,
+}
+impl EllipseSave {
+    pub fn uniforms<'a>(
+        &'a self,
+        sys: &'a mut SimpleCanvas,
+        radius: f32,
+        ratio: f32,
+    ) -> Uniforms<'a> {
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = EllipseSession::uniform_values(radius, ratio);
 
-        fn doop(a: Vec2<f32>) -> circle_program::Vertex {
-            circle_program::Vertex([a.x, a.y])
+        let buffer = self.buffer.get_info();
+        Uniforms {
+            common,
+            sys,
+            un: UniformVals::Circle(un),
+            buffer,
         }
+    }
+}
 
-        [
-            doop(start1),
-            doop(start2),
-            doop(end1),
-            doop(start2),
-            doop(end1),
-            doop(end2),
-        ]
+///Draws axis aligned ellipses.
+///Like circles, each ellipse is just one point.
+pub struct EllipseSession {
+    pub(crate) verts: Vec<circle_program::Vertex>,
+}
+
+impl EllipseSession {
+    pub fn new() -> Self {
+        EllipseSession { verts: Vec::new() }
+    }
+
+    fn uniform_values<'a>(radius: f32, ratio: f32) -> ProgramUniformValues<'a> {
+        //The point has to be big enough to fit the longer axis.
+        let ratio = ratio.max(0.0);
+        let longest = ratio.max(1.0);
+        let mut un = ProgramUniformValues::new(radius * longest, gl::POINTS);
+        un.axis_scale = [1.0 / longest, ratio / longest];
+        un
+    }
+
+    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> EllipseSave {
+        EllipseSave {
+            _ns: ns(),
+            buffer: vbo::StaticBuffer::new(&self.verts),
+        }
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        self.verts.append(&mut other.verts);
+    }
+
+    ///The radius is along the x axis. The radius along the y axis is the radius times the ratio.
+    pub fn send_and_uniforms<'a>(
+        &'a mut self,
+        sys: &'a mut SimpleCanvas,
+        radius: f32,
+        ratio: f32,
+    ) -> Uniforms<'a> {
+        sys.circle_buffer.send_to_gpu(&self.verts);
+
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = Self::uniform_values(radius, ratio);
+
+        let buffer = sys.circle_buffer.get_info(self.verts.len());
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Circle(un),
+            buffer,
+        }
     }
 
     #[inline(always)]
-    pub fn add(&mut self, start: PointType, end: PointType) -> &mut Self {
-        let a = Self::create_line(self.radius, start, end);
-        self.verts.extend_from_slice(&a);
+    pub fn add(&mut self, point: [f32; 2]) -> &mut Self {
+        self.verts.push(circle_program::Vertex(point));
+        self
+    }
+}
+
+pub struct RectSave {
+    _ns: NotSend,
+    buffer: vbo::StaticBuffer<circle_program::EdgeVertex>,
+}
+
+impl RectSave {
+    pub fn uniforms<'a>(&'a self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues {
+            local_edges: true,
+            ..ProgramUniformValues::new_with_edges(0.0, gl::TRIANGLES)
+        };
+        let buffer = self.buffer.get_info();
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer,
+        }
+    }
+}
+
+pub struct RectSession {
+    pub(crate) verts: Vec<circle_program::EdgeVertex>,
+}
+
+impl RectSession {
+    pub fn new() -> Self {
+        RectSession { verts: Vec::new() }
+    }
+
+    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> RectSave {
+        RectSave {
+            _ns: ns(),
+            buffer: vbo::StaticBuffer::new(&self.verts),
+        }
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        self.verts.append(&mut other.verts);
+    }
+    pub fn send_and_uniforms<'a>(&'a mut self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        sys.edge_buffer.send_to_gpu(&self.verts);
+
+        let common = UniformCommon {
+            color: sys.color,
+            offset: vec2same(0.0),
+        };
+        let un = ProgramUniformValues {
+            local_edges: true,
+            ..ProgramUniformValues::new_with_edges(0.0, gl::TRIANGLES)
+        };
+        let buffer = sys.edge_buffer.get_info(self.verts.len());
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer,
+        }
+    }
+
+    #[inline(always)]
+    fn create_rect(rect: [f32; 4]) -> [circle_program::EdgeVertex; 6] {
+        let rect:Rect<f32> = core::convert::From::from(rect);
+        Self::create_quad(rect.get_corners())
+    }
+
+    ///The corners are in the order top left, top right, bottom right, bottom left.
+    #[inline(always)]
+    fn create_quad(corners: [Vec2<f32>; 4]) -> [circle_program::EdgeVertex; 6] {
+        let [tl, tr, br, bl] = corners;
+        //let arr = [tr, tl, bl, bl, br, tr];
+
+        //Distances to the left, right, top and bottom edges.
+        fn doop(a: Vec2<f32>, edge: [u8; 4]) -> circle_program::EdgeVertex {
+            circle_program::EdgeVertex {
+                pos: [a.x, a.y],
+                edge,
+            }
+        }
+        let tl = doop(tl, [0, 255, 0, 255]);
+        let tr = doop(tr, [255, 0, 0, 255]);
+        let br = doop(br, [255, 0, 255, 0]);
+        let bl = doop(bl, [0, 255, 255, 0]);
+        [tr, tl, bl, bl, br, tr]
+    }
+    #[inline(always)]
+    pub fn add(&mut self, rect: [f32; 4]) -> &mut Self {
+        let arr = Self::create_rect(rect);
+        self.verts.extend_from_slice(&arr);
+        self
+    }
+
+    ///Add a rectangle rotated about its center.
+    ///The rotation is in radians and grows clockwise, the same as sprites.
+    #[inline(always)]
+    pub fn add_rotated(
+        &mut self,
+        center: PointType,
+        half_extents: [f32; 2],
+        angle: f32,
+    ) -> &mut Self {
+        let (s, c) = angle.sin_cos();
+        let center = vec2(center[0], center[1]);
+        let [hx, hy] = half_extents;
+        let corner = |x: f32, y: f32| center + vec2(x * c - y * s, x * s + y * c);
+
+        let arr = Self::create_quad([
+            corner(-hx, -hy),
+            corner(hx, -hy),
+            corner(hx, hy),
+            corner(-hx, hy),
+        ]);
+        self.verts.extend_from_slice(&arr);
+        self
+    }
+
+    ///Add a convex quadrilateral.
+    ///The corners are in the order top left, top right, bottom right, bottom left,
+    ///or any rotation of that order.
+    #[inline(always)]
+    pub fn add_quad(&mut self, corners: [PointType; 4]) -> &mut Self {
+        let [a, b, c, d] = corners;
+        let arr = Self::create_quad([
+            vec2(a[0], a[1]),
+            vec2(b[0], b[1]),
+            vec2(c[0], c[1]),
+            vec2(d[0], d[1]),
+        ]);
+        self.verts.extend_from_slice(&arr);
+        self
+    }
+}
+
+pub struct ColoredRectSave {
+    _ns: NotSend,
+    buffer: vbo::StaticBuffer<circle_program::ColoredEdgeVertex>,
+}
+
+impl ColoredRectSave {
+    pub fn uniforms<'a>(&'a self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new_with_edges_and_colors(0.0, gl::TRIANGLES);
+        let buffer = self.buffer.get_info();
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer,
+        }
+    }
+}
+
+///Rects that each have their own color, so differently colored rects
+///can be drawn in one draw call.
+///The colors are multiplied with the color set with `with_color`, which defaults to white.
+///Textures are not supported.
+pub struct ColoredRectSession {
+    pub(crate) verts: Vec<circle_program::ColoredEdgeVertex>,
+}
+
+impl ColoredRectSession {
+    pub fn new() -> Self {
+        ColoredRectSession { verts: Vec::new() }
+    }
+
+    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> ColoredRectSave {
+        ColoredRectSave {
+            _ns: ns(),
+            buffer: vbo::StaticBuffer::new(&self.verts),
+        }
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        self.verts.append(&mut other.verts);
+    }
+    pub fn send_and_uniforms<'a>(&'a mut self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        sys.colored_edge_buffer.send_to_gpu(&self.verts);
+
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new_with_edges_and_colors(0.0, gl::TRIANGLES);
+        let buffer = sys.colored_edge_buffer.get_info(self.verts.len());
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer,
+        }
+    }
+
+    #[inline(always)]
+    pub fn add(&mut self, rect: [f32; 4], color: [f32; 4]) -> &mut Self {
+        let color = circle_program::pack_color(color);
+        let arr = RectSession::create_rect(rect);
+        self.verts
+            .extend(arr.iter().map(|v| circle_program::ColoredEdgeVertex {
+                pos: v.pos,
+                edge: v.edge,
+                color,
+            }));
+        self
+    }
+}
+
+pub struct ArrowSave {
+    _ns: NotSend,
+    buffer: vbo::StaticBuffer<circle_program::EdgeVertex>,
+}
+impl ArrowSave {
+    pub fn uniforms<'a>(&'a self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new_with_edges(0.0, gl::TRIANGLES);
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer: self.buffer.get_info(),
+        }
+    }
+}
+
+///How long the head of an arrow is.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum HeadLength {
+    ///A fraction of the length of the whole arrow.
+    Relative(f32),
+    ///A fixed length, no matter how long the arrow is.
+    Absolute(f32),
+}
+
+///The look of the ends of an arrow.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ArrowStyle {
+    ///A solid triangle at the end point.
+    Filled,
+    ///Two lines in a V at the end point.
+    Open,
+    ///A solid triangle at both the start and the end point.
+    DoubleHeaded,
+    ///A solid triangle at the end point and feathers at the start point.
+    Feathered,
+}
+
+pub struct ArrowSession {
+    pub(crate) radius: f32,
+    pub(crate) head_length: HeadLength,
+    pub(crate) head_width: f32,
+    pub(crate) style: ArrowStyle,
+    pub(crate) verts: Vec<circle_program::EdgeVertex>,
+}
+
+impl ArrowSession {
+    pub fn new(radius: f32) -> Self {
+        ArrowSession {
+            radius,
+            head_length: HeadLength::Relative(0.2),
+            head_width: 2.5,
+            style: ArrowStyle::Filled,
+            verts: Vec::new(),
+        }
+    }
+
+    ///Set the length of the heads of arrows added after this call.
+    ///The head is never longer than the arrow itself.
+    pub fn with_head_length(&mut self, head_length: HeadLength) -> &mut Self {
+        self.head_length = head_length;
+        self
+    }
+
+    ///Set how far the heads of arrows added after this call stick out to either side,
+    ///as a multiple of the radius of the shaft.
+    pub fn with_head_width(&mut self, head_width: f32) -> &mut Self {
+        self.head_width = head_width;
+        self
+    }
+
+    ///Set the style of arrows added after this call.
+    pub fn with_style(&mut self, style: ArrowStyle) -> &mut Self {
+        self.style = style;
         self
     }
+    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> ArrowSave {
+        ArrowSave {
+            _ns: ns(),
+            buffer: vbo::StaticBuffer::new(&self.verts),
+        }
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        self.verts.append(&mut other.verts);
+    }
+    pub fn send_and_uniforms<'a>(&'a mut self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        sys.edge_buffer.send_to_gpu(&self.verts);
+
+        let common = UniformCommon {
+            color: sys.color,
+            offset: vec2same(0.0),
+        };
+        let un = ProgramUniformValues::new_with_edges(0.0, gl::TRIANGLES);
+        let buffer = sys.edge_buffer.get_info(self.verts.len());
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer,
+        }
+    }
+
+    #[inline(always)]
+    fn doop(a: Vec2<f32>, edge: [u8; 4]) -> circle_program::EdgeVertex {
+        circle_program::EdgeVertex {
+            pos: [a.x, a.y],
+            edge,
+        }
+    }
+
+    ///A solid head with its base centered on `base` and its point at `tip`,
+    ///sticking out by `side` to either side of the shaft.
+    ///It fades out along its two slanted sides but not along its base.
+    fn create_head(
+        base: Vec2<f32>,
+        tip: Vec2<f32>,
+        side: Vec2<f32>,
+        verts: &mut Vec<circle_program::EdgeVertex>,
+    ) {
+        verts.push(Self::doop(tip, [0, 0, 255, 255]));
+        verts.push(Self::doop(base + side, [0, 255, 255, 255]));
+        verts.push(Self::doop(base - side, [255, 0, 255, 255]));
+    }
+
+    fn create_arrow(&mut self, start: PointType, end: PointType) {
+        let radius = self.radius;
+        let half_width = radius * self.head_width;
+
+        let startv = vec2(start[0], start[1]);
+        let endv = vec2(end[0], end[1]);
+        let offset = endv - startv;
+        let length = (offset.x * offset.x + offset.y * offset.y).sqrt();
+        if !length.is_normal() {
+            //A zero length arrow does not point anywhere.
+            return;
+        }
+        let dir = offset / length;
+        let k = offset.rotate_90deg_right().normalize_to(1.0);
+
+        let max_head = match self.style {
+            ArrowStyle::DoubleHeaded => length * 0.5,
+            _ => length,
+        };
+        let head = match self.head_length {
+            HeadLength::Relative(a) => length * a,
+            HeadLength::Absolute(a) => a,
+        }
+        .max(0.0)
+        .min(max_head);
+
+        //The shaft shrinks away to nothing when the heads take up the whole arrow.
+        let line = |a: Vec2<f32>, b: Vec2<f32>, verts: &mut Vec<circle_program::EdgeVertex>| {
+            let d = b - a;
+            if d.x * d.x + d.y * d.y > 0.0 {
+                verts.extend_from_slice(&LineSession::create_line(radius, [a.x, a.y], [b.x, b.y]));
+            }
+        };
+
+        let end_base = endv - dir * head;
+        let verts = &mut self.verts;
+        match self.style {
+            ArrowStyle::Filled => {
+                line(startv, end_base, verts);
+                Self::create_head(end_base, endv, k * half_width, verts);
+            }
+            ArrowStyle::Open => {
+                line(startv, endv, verts);
+                line(endv, end_base + k * half_width, verts);
+                line(endv, end_base - k * half_width, verts);
+            }
+            ArrowStyle::DoubleHeaded => {
+                let start_base = startv + dir * head;
+                line(start_base, end_base, verts);
+                Self::create_head(end_base, endv, k * half_width, verts);
+                Self::create_head(start_base, startv, k * half_width, verts);
+            }
+            ArrowStyle::Feathered => {
+                line(startv, end_base, verts);
+                Self::create_head(end_base, endv, k * half_width, verts);
+
+                //Two pairs of feathers sweeping back from the shaft towards the start.
+                let spacing = (head * 0.5).min((length - head) * 0.5);
+                for i in 1..=2 {
+                    let root = startv + dir * (spacing * i as f32);
+                    let back = root - dir * spacing;
+                    line(root, back + k * half_width, verts);
+                    line(root, back - k * half_width, verts);
+                }
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn add(&mut self, start: PointType, end: PointType) -> &mut Self {
+        self.create_arrow(start, end);
+        self
+    }
+}
+
+pub struct LineSave {
+    _ns: NotSend,
+    buffer: vbo::StaticBuffer<circle_program::EdgeVertex>,
+}
+
+impl LineSave {
+    pub fn uniforms<'a>(&'a self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new_with_edges(0.0, gl::TRIANGLES);
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer: self.buffer.get_info(),
+        }
+    }
+}
+
+pub struct LineSession {
+    pub(crate) radius: f32,
+    pub(crate) verts: Vec<circle_program::EdgeVertex>,
+}
+
+impl LineSession {
+    pub fn new(radius: f32) -> Self {
+        LineSession {
+            radius,
+            verts: Vec::new(),
+        }
+    }
+
+    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> LineSave {
+        LineSave {
+            _ns: ns(),
+            buffer: vbo::StaticBuffer::new(&self.verts),
+        }
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        self.verts.append(&mut other.verts);
+    }
+    pub fn send_and_uniforms<'a>(&'a mut self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        sys.edge_buffer.send_to_gpu(&self.verts);
+
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+
+        let un = ProgramUniformValues::new_with_edges(0.0, gl::TRIANGLES);
+        let buffer = sys.edge_buffer.get_info(self.verts.len());
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer,
+        }
+    }
+
+    #[inline(always)]
+    fn create_line(
+        radius: f32,
+        start: PointType,
+        end: PointType,
+    ) -> [circle_program::EdgeVertex; 6] {
+        let start = vec2(start[0], start[1]); //TODO a program that detected bad uses like this would be cool
+        let end = vec2(end[0], end[1]);
+
+        let offset = end - start;
+        let k = offset.rotate_90deg_right().normalize_to(1.0);
+        let start1 = start + k * radius;
+        let start2 = start - k * radius;
+
+        let end1 = end + k * radius;
+        let end2 = end - k * radius;
+
+        //let arr = [start1, start2, end1, start2, end1, end2];
+
+        fn doop(a: Vec2<f32>, edge: [u8; 4]) -> circle_program::EdgeVertex {
+            circle_program::EdgeVertex {
+                pos: [a.x, a.y],
+                edge,
+            }
+        }
+
+        //Distances to either side of the line.
+        let side1 = [0, 255, 255, 255];
+        let side2 = [255, 0, 255, 255];
+        [
+            doop(start1, side1),
+            doop(start2, side2),
+            doop(end1, side1),
+            doop(start2, side2),
+            doop(end1, side1),
+            doop(end2, side2),
+        ]
+    }
+
+    #[inline(always)]
+    pub fn add(&mut self, start: PointType, end: PointType) -> &mut Self {
+        let a = Self::create_line(self.radius, start, end);
+        self.verts.extend_from_slice(&a);
+        self
+    }
+}
+
+///How two connected segments of a polyline are joined at a corner.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum JoinStyle {
+    ///Extend the outer edges of the segments until they meet.
+    ///If the point would be further than `limit` times the radius from the corner,
+    ///a bevel is drawn instead.
+    Miter { limit: f32 },
+    ///Cut the corner off with a straight edge.
+    Bevel,
+    ///Round the corner off with an arc.
+    Round,
+}
+
+///How the two open ends of a polyline are drawn.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CapStyle {
+    ///The line stops exactly at the end point.
+    Butt,
+    ///The line extends past the end point by the radius.
+    Square,
+    ///The line ends with a half circle centered at the end point.
+    Round,
+}
+
+///Which parts of a path with overlapping subpaths are inside of it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FillRule {
+    ///A point is inside if a ray from it crosses the path an odd number of times.
+    EvenOdd,
+    ///A point is inside if the path winds around it at least once,
+    ///counting clockwise and counter-clockwise windings against each other.
+    NonZero,
+}
+
+///Triangulate the subpaths as one filled shape.
+///Subpaths are treated as closed, and are assumed to not cross each other.
+///Each subpath is filled minus the subpaths directly inside of it, if the rule says
+///that the region just inside of its edge is part of the shape.
+fn fill_subpaths(subpaths: &[svg::SubPath], rule: FillRule, out: &mut Vec<circle_program::Vertex>) {
+    let rings: Vec<&[PointType]> = subpaths
+        .iter()
+        .filter(|s| s.points.len() >= 3)
+        .map(|s| &s.points[..])
+        .collect();
+    let parents: Vec<Vec<usize>> = (0..rings.len())
+        .map(|i| {
+            (0..rings.len())
+                .filter(|&j| j != i && svg::contains(rings[j], rings[i][0]))
+                .collect()
+        })
+        .collect();
+    let winding = |i: usize| {
+        if triangulate::signed_area(rings[i]) > 0.0 {
+            1
+        } else {
+            -1
+        }
+    };
+
+    for (i, outline) in rings.iter().enumerate() {
+        let depth = parents[i].len();
+        let filled = match rule {
+            FillRule::EvenOdd => depth % 2 == 0,
+            FillRule::NonZero => {
+                winding(i) + parents[i].iter().map(|&j| winding(j)).sum::<i32>() != 0
+            }
+        };
+        if !filled {
+            continue;
+        }
+        let holes: Vec<&[PointType]> = (0..rings.len())
+            .filter(|&j| parents[j].len() == depth + 1 && parents[j].contains(&i))
+            .map(|j| rings[j])
+            .collect();
+        triangulate::triangulate(outline, &holes, out);
+    }
+}
+
+pub struct PolylineSave {
+    _ns: NotSend,
+    buffer: vbo::StaticBuffer<circle_program::Vertex>,
+}
+
+impl PolylineSave {
+    pub fn uniforms<'a>(&'a self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new(0.0, gl::TRIANGLES);
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer: self.buffer.get_info(),
+        }
+    }
+}
+
+///Draws connected paths as one continuous stroke.
+///Unlike the line session, the segments do not overlap at the corners,
+///so transparent paths are drawn with an even color.
+pub struct PolylineSession {
+    pub(crate) radius: f32,
+    pub(crate) tolerance: f32,
+    pub(crate) join: JoinStyle,
+    pub(crate) cap: CapStyle,
+    pub(crate) dash: Vec<f32>,
+    pub(crate) dash_phase: f32,
+    pub(crate) verts: Vec<circle_program::Vertex>,
+}
+
+impl PolylineSession {
+    ///The tolerance is how far in world units round joins and caps are allowed to stray from true arcs.
+    pub fn new(radius: f32, tolerance: f32) -> Self {
+        PolylineSession {
+            radius,
+            tolerance,
+            join: JoinStyle::Miter { limit: 4.0 },
+            cap: CapStyle::Butt,
+            dash: Vec::new(),
+            dash_phase: 0.0,
+            verts: Vec::new(),
+        }
+    }
+
+    ///Dash paths added after this call.
+    ///The pattern alternates between the lengths of dashes and the gaps between them, in world units.
+    ///It is measured continuously along each path, starting `phase` units into the pattern,
+    ///so moving the phase a little every frame makes the dashes crawl along the path.
+    ///A dash of length zero with a round or square cap draws a dot.
+    ///On closed paths a dash that runs over the first point is drawn in one piece.
+    ///An empty pattern draws solid paths again, and so does a pattern that repeats
+    ///more than a hundred thousand times along one segment of a path.
+    pub fn with_dash(&mut self, pattern: &[f32], phase: f32) -> &mut Self {
+        self.dash.clear();
+        self.dash.extend_from_slice(pattern);
+        self.dash_phase = phase;
+        self
+    }
+
+    ///Set the join style used by paths added after this call.
+    pub fn with_join(&mut self, join: JoinStyle) -> &mut Self {
+        self.join = join;
+        self
+    }
+
+    ///Set the cap style used by paths added after this call.
+    pub fn with_cap(&mut self, cap: CapStyle) -> &mut Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> PolylineSave {
+        PolylineSave {
+            _ns: ns(),
+            buffer: vbo::StaticBuffer::new(&self.verts),
+        }
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        self.verts.append(&mut other.verts);
+    }
+
+    pub fn send_and_uniforms<'a>(&'a mut self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        sys.circle_buffer.send_to_gpu(&self.verts);
+
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new(0.0, gl::TRIANGLES);
+        let buffer = sys.circle_buffer.get_info(self.verts.len());
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer,
+        }
+    }
+
+    fn add_stroke(&mut self, path: &[PointType], closed: bool) {
+        let (radius, join, cap, tolerance) = (self.radius, self.join, self.cap, self.tolerance);
+        let dashes = if self.dash.is_empty() {
+            None
+        } else {
+            stroke::dash(path, closed, &self.dash, self.dash_phase)
+        };
+        let verts = &mut self.verts;
+        match dashes {
+            Some(dashes) => {
+                for dash in dashes {
+                    stroke::stroke_within(&dash, false, radius, join, cap, tolerance, verts);
+                }
+            }
+            None => stroke::stroke_within(path, closed, radius, join, cap, tolerance, verts),
+        }
+    }
+
+    ///Add an open path through the given points. Both ends get the current cap style.
+    pub fn add_path(&mut self, path: &[PointType]) -> &mut Self {
+        self.add_stroke(path, false);
+        self
+    }
+
+    ///Add a closed path through the given points. The last point is joined back to the first.
+    pub fn add_closed_path(&mut self, path: &[PointType]) -> &mut Self {
+        self.add_stroke(path, true);
+        self
+    }
+}
+
+pub struct ArcSave {
+    _ns: NotSend,
+    buffer: vbo::StaticBuffer<circle_program::Vertex>,
+}
+
+impl ArcSave {
+    pub fn uniforms<'a>(&'a self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new(0.0, gl::TRIANGLES);
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer: self.buffer.get_info(),
+        }
+    }
+}
+
+///Draws circular arcs and filled pie sectors.
+///Angles are in radians. 0 points along the x axis and angles grow clockwise.
+///Sweeps are clamped to a full turn either way, and arcs with angles that are not finite are skipped.
+pub struct ArcSession {
+    pub(crate) radius: f32,
+    pub(crate) tolerance: f32,
+    pub(crate) verts: Vec<circle_program::Vertex>,
+}
+
+impl ArcSession {
+    ///The tolerance is how far in world units the drawn arcs are allowed to stray from true arcs.
+    pub fn new(radius: f32, tolerance: f32) -> Self {
+        ArcSession {
+            radius,
+            tolerance,
+            verts: Vec::new(),
+        }
+    }
+
+    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> ArcSave {
+        ArcSave {
+            _ns: ns(),
+            buffer: vbo::StaticBuffer::new(&self.verts),
+        }
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        self.verts.append(&mut other.verts);
+    }
+
+    pub fn send_and_uniforms<'a>(&'a mut self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        sys.circle_buffer.send_to_gpu(&self.verts);
+
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new(0.0, gl::TRIANGLES);
+        let buffer = sys.circle_buffer.get_info(self.verts.len());
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer,
+        }
+    }
+
+    ///Split an arc into `n` pieces, returning the `n+1` points from the start angle to the end angle.
+    fn arc_points(
+        center: PointType,
+        radius: f32,
+        start_angle: f32,
+        sweep: f32,
+        n: usize,
+    ) -> impl Iterator<Item = circle_program::Vertex> {
+        (0..=n).map(move |i| {
+            let a = start_angle + sweep * (i as f32 / n as f32);
+            circle_program::Vertex([center[0] + a.cos() * radius, center[1] + a.sin() * radius])
+        })
+    }
+
+    ///None if either angle is not finite, since `max` and `min` would turn a NaN sweep into a full turn.
+    #[inline(always)]
+    fn clamp_sweep(start_angle: f32, sweep: f32) -> Option<f32> {
+        if !start_angle.is_finite() || !sweep.is_finite() {
+            return None;
+        }
+        let tau = core::f32::consts::PI * 2.0;
+        Some(sweep.max(-tau).min(tau))
+    }
+
+    ///Add an arc stroked with the thickness of this session.
+    pub fn add(
+        &mut self,
+        center: PointType,
+        radius: f32,
+        start_angle: f32,
+        sweep: f32,
+    ) -> &mut Self {
+        let sweep = match Self::clamp_sweep(start_angle, sweep) {
+            Some(sweep) => sweep,
+            None => return self,
+        };
+        let inner = (radius - self.radius).max(0.0);
+        let outer = radius + self.radius;
+        let n = stroke::arc_segments_within(outer, sweep, self.tolerance);
+
+        let inner = Self::arc_points(center, inner, start_angle, sweep, n);
+        let outer = Self::arc_points(center, outer, start_angle, sweep, n);
+
+        let mut prev = None;
+        for (a, b) in inner.zip(outer) {
+            if let Some((pa, pb)) = prev {
+                self.verts.extend_from_slice(&[pa, pb, a, pb, b, a]);
+            }
+            prev = Some((a, b));
+        }
+        self
+    }
+
+    ///Add a filled pie sector.
+    pub fn add_sector(
+        &mut self,
+        center: PointType,
+        radius: f32,
+        start_angle: f32,
+        sweep: f32,
+    ) -> &mut Self {
+        let sweep = match Self::clamp_sweep(start_angle, sweep) {
+            Some(sweep) => sweep,
+            None => return self,
+        };
+        let n = stroke::arc_segments_within(radius, sweep, self.tolerance);
+
+        let c = circle_program::Vertex(center);
+        let mut prev = None;
+        for p in Self::arc_points(center, radius, start_angle, sweep, n) {
+            if let Some(prev) = prev {
+                self.verts.extend_from_slice(&[c, prev, p]);
+            }
+            prev = Some(p);
+        }
+        self
+    }
+}
+
+pub struct CurveSave {
+    _ns: NotSend,
+    buffer: vbo::StaticBuffer<circle_program::Vertex>,
+}
+
+impl CurveSave {
+    pub fn uniforms<'a>(&'a self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new(0.0, gl::TRIANGLES);
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer: self.buffer.get_info(),
+        }
+    }
+}
+
+///Draws quadratic and cubic bezier curves.
+///Curves are split into just enough segments to look smooth at the
+///viewport scale the session was created at.
+pub struct CurveSession {
+    pub(crate) radius: f32,
+    pub(crate) tolerance: f32,
+    pub(crate) cap: CapStyle,
+    pub(crate) verts: Vec<circle_program::Vertex>,
+}
+
+impl CurveSession {
+    ///The tolerance is how far in world units the drawn curve is allowed to stray from the true curve.
+    pub fn new(radius: f32, tolerance: f32) -> Self {
+        CurveSession {
+            radius,
+            tolerance,
+            cap: CapStyle::Butt,
+            verts: Vec::new(),
+        }
+    }
+
+    ///Set the cap style used by curves added after this call.
+    pub fn with_cap(&mut self, cap: CapStyle) -> &mut Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> CurveSave {
+        CurveSave {
+            _ns: ns(),
+            buffer: vbo::StaticBuffer::new(&self.verts),
+        }
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        self.verts.append(&mut other.verts);
+    }
+
+    pub fn send_and_uniforms<'a>(&'a mut self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        sys.circle_buffer.send_to_gpu(&self.verts);
+
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new(0.0, gl::TRIANGLES);
+        let buffer = sys.circle_buffer.get_info(self.verts.len());
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer,
+        }
+    }
+
+    fn add_points(&mut self, points: &[PointType]) {
+        let join = JoinStyle::Miter { limit: 4.0 };
+        let (radius, cap, tolerance) = (self.radius, self.cap, self.tolerance);
+        stroke::stroke_within(points, false, radius, join, cap, tolerance, &mut self.verts);
+    }
+
+    ///Add a quadratic bezier curve from `p0` to `p1` with control point `c`.
+    pub fn add_quad(&mut self, p0: PointType, c: PointType, p1: PointType) -> &mut Self {
+        let mut points = vec![p0];
+        curve::flatten_quad(p0, c, p1, self.tolerance, &mut points);
+        self.add_points(&points);
+        self
+    }
+
+    ///Add a cubic bezier curve from `p0` to `p1` with control points `c0` and `c1`.
+    pub fn add_cubic(
+        &mut self,
+        p0: PointType,
+        c0: PointType,
+        c1: PointType,
+        p1: PointType,
+    ) -> &mut Self {
+        let mut points = vec![p0];
+        curve::flatten_cubic(p0, c0, c1, p1, self.tolerance, &mut points);
+        self.add_points(&points);
+        self
+    }
+}
+
+pub struct RoundedRectSave {
+    _ns: NotSend,
+    buffer: vbo::StaticBuffer<circle_program::UvVertex>,
+}
+
+impl RoundedRectSave {
+    pub fn uniforms<'a>(&'a self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new_with_uvs(0.0, gl::TRIANGLES);
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer: self.buffer.get_info(),
+        }
+    }
+}
+
+///Draws filled or outlined axis aligned rectangles with rounded corners.
+///The corners are split into just enough segments to look smooth at the
+///viewport scale the session was created at.
+///Outlines are stroked with the radius of the session, like polylines.
+pub struct RoundedRectSession {
+    pub(crate) corner_radius: f32,
+    pub(crate) radius: f32,
+    pub(crate) tolerance: f32,
+    pub(crate) verts: Vec<circle_program::UvVertex>,
+}
+
+impl RoundedRectSession {
+    ///The tolerance is how far in world units the drawn corners are allowed to stray from true arcs.
+    pub fn new(corner_radius: f32, radius: f32, tolerance: f32) -> Self {
+        RoundedRectSession {
+            corner_radius,
+            radius,
+            tolerance,
+            verts: Vec::new(),
+        }
+    }
+
+    ///Set the corner radius used by rects added after this call.
+    pub fn with_corner_radius(&mut self, corner_radius: f32) -> &mut Self {
+        self.corner_radius = corner_radius;
+        self
+    }
+
+    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> RoundedRectSave {
+        RoundedRectSave {
+            _ns: ns(),
+            buffer: vbo::StaticBuffer::new(&self.verts),
+        }
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        self.verts.append(&mut other.verts);
+    }
+
+    pub fn send_and_uniforms<'a>(&'a mut self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        sys.uv_buffer.send_to_gpu(&self.verts);
+
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new_with_uvs(0.0, gl::TRIANGLES);
+        let buffer = sys.uv_buffer.get_info(self.verts.len());
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer,
+        }
+    }
+
+    ///The outline of the rect going clockwise from the top right corner.
+    fn outline(&self, rect: [f32; 4]) -> Vec<PointType> {
+        let [x0, x1, y0, y1] = rect;
+        let (x0, x1) = (x0.min(x1), x0.max(x1));
+        let (y0, y1) = (y0.min(y1), y0.max(y1));
+
+        //The corners can't be any rounder than half the shorter side.
+        let r = self
+            .corner_radius
+            .min((x1 - x0) * 0.5)
+            .min((y1 - y0) * 0.5)
+            .max(0.0);
+
+        let quarter = core::f32::consts::PI * 0.5;
+        let n = if r > 0.0 {
+            stroke::arc_segments_within(r, quarter, self.tolerance)
+        } else {
+            0
+        };
+
+        let corners = [
+            ([x1 - r, y0 + r], -quarter),
+            ([x1 - r, y1 - r], 0.0),
+            ([x0 + r, y1 - r], quarter),
+            ([x0 + r, y0 + r], quarter * 2.0),
+        ];
+
+        let mut points = Vec::with_capacity(4 * (n + 1));
+        for &(c, start) in corners.iter() {
+            for i in 0..=n {
+                let a = if n == 0 {
+                    start
+                } else {
+                    start + quarter * (i as f32 / n as f32)
+                };
+                points.push([c[0] + a.cos() * r, c[1] + a.sin() * r]);
+            }
+        }
+        points
+    }
+
+    ///Add a filled rect. The rect is in the same format as the rect session.
+    pub fn add(&mut self, rect: [f32; 4]) -> &mut Self {
+        let points = self.outline(rect);
+        let center = [(rect[0] + rect[1]) * 0.5, (rect[2] + rect[3]) * 0.5];
+        let center = circle_program::Vertex(center);
+
+        //Rounded rects are convex so they can be fanned out from the center.
+        let mut verts = Vec::with_capacity(points.len() * 3);
+        for i in 0..points.len() {
+            let a = points[i];
+            let b = points[(i + 1) % points.len()];
+            verts.extend_from_slice(&[
+                center,
+                circle_program::Vertex(a),
+                circle_program::Vertex(b),
+            ]);
+        }
+        push_with_uvs(&verts, bounds(&points), &mut self.verts);
+        self
+    }
+
+    ///Add an outlined rect stroked with the radius of this session.
+    ///The outline is centered on the edge of the rect.
+    pub fn add_outline(&mut self, rect: [f32; 4]) -> &mut Self {
+        let points = self.outline(rect);
+        let (join, cap) = (JoinStyle::Miter { limit: 4.0 }, CapStyle::Butt);
+        let (radius, tolerance) = (self.radius, self.tolerance);
+        let mut verts = Vec::new();
+        stroke::stroke_within(&points, true, radius, join, cap, tolerance, &mut verts);
+        push_with_uvs(&verts, bounds(&points), &mut self.verts);
+        self
+    }
+}
+
+pub struct RegularPolygonSave {
+    _ns: NotSend,
+    buffer: vbo::StaticBuffer<circle_program::UvVertex>,
+}
+
+impl RegularPolygonSave {
+    pub fn uniforms<'a>(&'a self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new_with_uvs(0.0, gl::TRIANGLES);
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer: self.buffer.get_info(),
+        }
+    }
+}
+
+///Draws regular polygons like triangles, hexagons and octagons, as well as stars.
+///With no rotation the first corner points straight up.
+///Rotations are in radians and grow clockwise.
+pub struct RegularPolygonSession {
+    pub(crate) verts: Vec<circle_program::UvVertex>,
+}
+
+impl RegularPolygonSession {
+    pub fn new() -> Self {
+        RegularPolygonSession { verts: Vec::new() }
+    }
+
+    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> RegularPolygonSave {
+        RegularPolygonSave {
+            _ns: ns(),
+            buffer: vbo::StaticBuffer::new(&self.verts),
+        }
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        self.verts.append(&mut other.verts);
+    }
+
+    pub fn send_and_uniforms<'a>(&'a mut self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        sys.uv_buffer.send_to_gpu(&self.verts);
+
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new_with_uvs(0.0, gl::TRIANGLES);
+        let buffer = sys.uv_buffer.get_info(self.verts.len());
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer,
+        }
+    }
+
+    ///Fan out triangles from the center to `count` corners evenly spaced around it.
+    ///The radius of each corner is picked by `radius`.
+    ///The uvs span the square around the biggest radius, turned along with the shape.
+    #[inline(always)]
+    fn fan(
+        &mut self,
+        center: PointType,
+        count: usize,
+        rotation: f32,
+        radius: impl Fn(usize) -> f32,
+    ) {
+        let step = core::f32::consts::PI * 2.0 / count as f32;
+        let start = -core::f32::consts::PI * 0.5;
+        let extent = (0..count).map(&radius).fold(0.0f32, f32::max) * 2.0;
+        let (sin, cos) = rotation.sin_cos();
+        let corner = |i: usize| {
+            let a = start + step * i as f32;
+            let r = radius(i % count);
+            let (x, y) = (a.cos() * r, a.sin() * r);
+            let uv = if extent > 0.0 {
+                [0.5 + x / extent, 0.5 + y / extent]
+            } else {
+                [0.5; 2]
+            };
+            circle_program::UvVertex {
+                pos: [center[0] + x * cos - y * sin, center[1] + x * sin + y * cos],
+                uv,
+            }
+        };
+
+        let c = circle_program::UvVertex {
+            pos: center,
+            uv: [0.5; 2],
+        };
+        for i in 0..count {
+            self.verts.extend_from_slice(&[c, corner(i), corner(i + 1)]);
+        }
+    }
+
+    ///Add a regular polygon with the given number of sides.
+    ///Fewer than 3 sides draws nothing.
+    pub fn add(
+        &mut self,
+        center: PointType,
+        radius: f32,
+        sides: usize,
+        rotation: f32,
+    ) -> &mut Self {
+        if sides >= 3 {
+            self.fan(center, sides, rotation, |_| radius);
+        }
+        self
+    }
+
+    ///Add a star with the given number of points.
+    ///The tips of the star lie on the outer radius and the corners between them
+    ///lie on the inner radius.
+    ///Fewer than 2 points draws nothing.
+    pub fn add_star(
+        &mut self,
+        center: PointType,
+        outer_radius: f32,
+        inner_radius: f32,
+        points: usize,
+        rotation: f32,
+    ) -> &mut Self {
+        if points >= 2 {
+            self.fan(center, points * 2, rotation, |i| {
+                if i % 2 == 0 {
+                    outer_radius
+                } else {
+                    inner_radius
+                }
+            });
+        }
+        self
+    }
+}
+
+pub struct TriangleSave {
+    _ns: NotSend,
+    buffer: vbo::StaticBuffer<circle_program::Vertex>,
+}
+
+impl TriangleSave {
+    pub fn uniforms<'a>(&'a self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new(0.0, gl::TRIANGLES);
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer: self.buffer.get_info(),
+        }
+    }
+}
+
+///Draws raw triangles.
+///This is an escape hatch for geometry that none of the other sessions can build.
+pub struct TriangleSession {
+    pub(crate) verts: Vec<circle_program::Vertex>,
+}
+
+impl TriangleSession {
+    pub fn new() -> Self {
+        TriangleSession { verts: Vec::new() }
+    }
+
+    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> TriangleSave {
+        TriangleSave {
+            _ns: ns(),
+            buffer: vbo::StaticBuffer::new(&self.verts),
+        }
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        self.verts.append(&mut other.verts);
+    }
+
+    pub fn send_and_uniforms<'a>(&'a mut self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        sys.circle_buffer.send_to_gpu(&self.verts);
+
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new(0.0, gl::TRIANGLES);
+        let buffer = sys.circle_buffer.get_info(self.verts.len());
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer,
+        }
+    }
+
+    #[inline(always)]
+    pub fn add(&mut self, a: PointType, b: PointType, c: PointType) -> &mut Self {
+        self.verts.extend_from_slice(&[
+            circle_program::Vertex(a),
+            circle_program::Vertex(b),
+            circle_program::Vertex(c),
+        ]);
+        self
+    }
+
+    ///Add a list of triangles, three points per triangle.
+    ///Any trailing points that do not make up a whole triangle are ignored.
+    pub fn add_list(&mut self, points: &[PointType]) -> &mut Self {
+        let whole = points.len() - points.len() % 3;
+        self.verts
+            .extend(points[..whole].iter().map(|&p| circle_program::Vertex(p)));
+        self
+    }
+}
+
+///An index type that a mesh can use. Implemented for u16 and u32.
+pub trait MeshIndex: Copy + core::fmt::Debug {
+    #[doc(hidden)]
+    const GL_TYPE: u32;
+    #[doc(hidden)]
+    const MAX: usize;
+    #[doc(hidden)]
+    fn from_usize(a: usize) -> Self;
+    #[doc(hidden)]
+    fn to_usize(self) -> usize;
+}
+
+impl MeshIndex for u16 {
+    const GL_TYPE: u32 = gl::UNSIGNED_SHORT;
+    const MAX: usize = u16::MAX as usize;
+    #[inline(always)]
+    fn from_usize(a: usize) -> Self {
+        assert!(a <= u16::MAX as usize, "Too many vertices for u16 indices");
+        a as u16
+    }
+    #[inline(always)]
+    fn to_usize(self) -> usize {
+        self as usize
+    }
+}
+
+impl MeshIndex for u32 {
+    const GL_TYPE: u32 = gl::UNSIGNED_INT;
+    const MAX: usize = u32::MAX as usize;
+    #[inline(always)]
+    fn from_usize(a: usize) -> Self {
+        assert!(a <= u32::MAX as usize, "Too many vertices for u32 indices");
+        a as u32
+    }
+    #[inline(always)]
+    fn to_usize(self) -> usize {
+        self as usize
+    }
+}
+
+pub struct MeshSave {
+    _ns: NotSend,
+    buffer: vbo::StaticBuffer<circle_program::Vertex>,
+    indices: vbo::StaticIndexBuffer,
+}
+
+impl MeshSave {
+    pub fn uniforms<'a>(&'a self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new(0.0, gl::TRIANGLES);
+        let (index, length) = self.indices.get_info();
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer: self.buffer.get_info().with_indices(index, length),
+        }
+    }
+}
+
+///Draws indexed triangle meshes.
+///The vertices are sent once and the triangles are made up of indices into them,
+///which saves sending shared vertices over and over.
+pub struct MeshSession<I: MeshIndex> {
+    pub(crate) verts: Vec<circle_program::Vertex>,
+    pub(crate) indices: Vec<I>,
+}
+
+impl<I: MeshIndex> MeshSession<I> {
+    pub fn new() -> Self {
+        MeshSession {
+            verts: Vec::new(),
+            indices: Vec::new(),
+        }
+    }
+
+    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> MeshSave {
+        MeshSave {
+            _ns: ns(),
+            buffer: vbo::StaticBuffer::new(&self.verts),
+            indices: vbo::StaticIndexBuffer::new(&self.indices, I::GL_TYPE),
+        }
+    }
+
+    ///Panics with `u16` indices if the combined session has more than 65536 vertices.
+    pub fn append(&mut self, other: &mut Self) {
+        self.assert_room(other.verts.len());
+        let base = self.verts.len();
+        self.verts.append(&mut other.verts);
+        self.indices.extend(
+            other
+                .indices
+                .drain(..)
+                .map(|i| I::from_usize(i.to_usize() + base)),
+        );
+    }
+
+    pub fn send_and_uniforms<'a>(&'a mut self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        sys.circle_buffer.send_to_gpu(&self.verts);
+        let index = sys.index_buffer.send_to_gpu(&self.indices, I::GL_TYPE);
+
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new(0.0, gl::TRIANGLES);
+        let buffer = sys
+            .circle_buffer
+            .get_info(self.verts.len())
+            .with_indices(index, self.indices.len());
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer,
+        }
+    }
+
+    ///Add a mesh. Every three indices make up a triangle.
+    ///The indices are relative to the vertices passed in,
+    ///so several meshes can be added to one session.
+    ///Panics if an index is not less than the number of vertices passed in.
+    ///With `u16` indices, it also panics once the session holds more than 65536 vertices in total,
+    ///counting every mesh added or appended to it.
+    ///Nothing is added if it panics.
+    pub fn add(&mut self, vertices: &[PointType], indices: &[I]) -> &mut Self {
+        let whole = indices.len() - indices.len() % 3;
+        let indices = &indices[..whole];
+        if let Some(i) = indices.iter().find(|i| i.to_usize() >= vertices.len()) {
+            panic!(
+                "Mesh index {:?} is out of range of the {} vertices",
+                i,
+                vertices.len()
+            );
+        }
+        self.assert_room(vertices.len());
+
+        let base = self.verts.len();
+        self.verts
+            .extend(vertices.iter().map(|&p| circle_program::Vertex(p)));
+        self.indices
+            .extend(indices.iter().map(|&i| I::from_usize(i.to_usize() + base)));
+        self
+    }
+
+    ///Check that every one of `count` more vertices could be indexed, before anything is changed.
+    fn assert_room(&self, count: usize) {
+        let last = (self.verts.len() + count).saturating_sub(1);
+        assert!(last <= I::MAX, "Too many vertices for the mesh index type");
+    }
+}
+
+pub struct SvgSave {
+    _ns: NotSend,
+    fill: vbo::StaticBuffer<circle_program::Vertex>,
+    stroke: vbo::StaticBuffer<circle_program::Vertex>,
+}
+
+impl SvgSave {
+    ///Draw the insides of the filled shapes.
+    pub fn fill_uniforms<'a>(&'a self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new(0.0, gl::TRIANGLES);
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer: self.fill.get_info(),
+        }
+    }
+
+    ///Draw the outlines of the stroked shapes.
+    pub fn stroke_uniforms<'a>(&'a self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new(0.0, gl::TRIANGLES);
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer: self.stroke.get_info(),
+        }
+    }
+}
+
+///Loads vector art from svg into triangles.
+///Filled shapes and strokes are kept apart so that they can be drawn in different colors,
+///but the colors in the svg itself are ignored.
+///Subpaths that lie inside of another subpath of the same path are cut out of it as holes
+///or filled in, depending on the `fill-rule` of the element, which is `nonzero` by default like in svg.
+///Curves are flattened when they are added, so they stay smooth down to the tolerance.
+///Strokes are as wide as a polyline with half the `stroke-width` as its radius,
+///after the width is scaled along with the placement.
+pub struct SvgSession {
+    point_mul: f32,
+    tolerance: f32,
+    scale: f32,
+    offset: [f32; 2],
+    pub(crate) fill: Vec<circle_program::Vertex>,
+    pub(crate) stroke: Vec<circle_program::Vertex>,
+}
+
+impl SvgSession {
+    ///Stroke radii are multiplied by `point_mul`, the same as the radius of polylines.
+    pub fn new(point_mul: f32, tolerance: f32) -> Self {
+        SvgSession {
+            point_mul,
+            tolerance,
+            scale: 1.0,
+            offset: [0.0; 2],
+            fill: Vec::new(),
+            stroke: Vec::new(),
+        }
+    }
+
+    ///Set how far flattened curves may stray from the true curve, in world units.
+    pub fn with_tolerance(&mut self, tolerance: f32) -> &mut Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    ///Scale the svg coordinates and then move them by the offset
+    ///for everything added after this call.
+    pub fn with_placement(&mut self, scale: f32, offset: [f32; 2]) -> &mut Self {
+        self.scale = scale;
+        self.offset = offset;
+        self
+    }
+
+    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> SvgSave {
+        SvgSave {
+            _ns: ns(),
+            fill: vbo::StaticBuffer::new(&self.fill),
+            stroke: vbo::StaticBuffer::new(&self.stroke),
+        }
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        self.fill.append(&mut other.fill);
+        self.stroke.append(&mut other.stroke);
+    }
+
+    ///Add every path and basic shape in an svg document.
+    pub fn add_document(&mut self, svg: &str) -> &mut Self {
+        for shape in svg::parse_document(svg, self.tolerance / self.scale) {
+            self.add_shape(shape);
+        }
+        self
+    }
+
+    ///Add svg path data, as found in the `d` attribute of a path.
+    ///The path is filled with the given rule, or left unfilled if there is none.
+    pub fn add_path(
+        &mut self,
+        d: &str,
+        fill: Option<FillRule>,
+        stroke_width: Option<f32>,
+    ) -> &mut Self {
+        let subpaths = svg::parse_path(d, self.tolerance / self.scale);
+        self.add_shape(svg::Shape {
+            subpaths,
+            fill,
+            stroke: stroke_width,
+        });
+        self
+    }
+
+    fn add_shape(&mut self, mut shape: svg::Shape) {
+        let (scale, offset) = (self.scale, self.offset);
+        for sub in shape.subpaths.iter_mut() {
+            for p in sub.points.iter_mut() {
+                *p = [p[0] * scale + offset[0], p[1] * scale + offset[1]];
+            }
+        }
+
+        if let Some(rule) = shape.fill {
+            fill_subpaths(&shape.subpaths, rule, &mut self.fill);
+        }
+        if let Some(width) = shape.stroke {
+            for sub in shape.subpaths.iter() {
+                stroke::stroke_within(
+                    &sub.points,
+                    sub.closed,
+                    width * scale * 0.5 * self.point_mul,
+                    JoinStyle::Miter { limit: 4.0 },
+                    CapStyle::Butt,
+                    self.tolerance,
+                    &mut self.stroke,
+                );
+            }
+        }
+    }
+}
+
+pub struct PathSave {
+    _ns: NotSend,
+    buffer: vbo::StaticBuffer<circle_program::Vertex>,
+}
+
+impl PathSave {
+    pub fn uniforms<'a>(&'a self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new(0.0, gl::TRIANGLES);
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer: self.buffer.get_info(),
+        }
+    }
+}
+
+///Builds up shapes the same way as the html canvas path api.
+///A path is made of subpaths that are started with `move_to()` and extended with
+///lines and curves. Calling `fill()` or `stroke()` turns the current path into triangles,
+///and the path is kept until `begin_path()` so that it can be both filled and stroked.
+///Subpaths that are filled are assumed to not cross themselves or each other.
+///Strokes use the radius of the session, like polylines.
+pub struct PathSession {
+    radius: f32,
+    tolerance: f32,
+    join: JoinStyle,
+    cap: CapStyle,
+    subpaths: Vec<svg::SubPath>,
+    pub(crate) verts: Vec<circle_program::Vertex>,
+}
+
+impl PathSession {
+    ///The tolerance is how far in world units flattened curves are allowed to stray from the true curve.
+    pub fn new(radius: f32, tolerance: f32) -> Self {
+        PathSession {
+            radius,
+            tolerance,
+            join: JoinStyle::Miter { limit: 4.0 },
+            cap: CapStyle::Butt,
+            subpaths: Vec::new(),
+            verts: Vec::new(),
+        }
+    }
+
+    ///Set the join style used by strokes after this call.
+    pub fn with_join(&mut self, join: JoinStyle) -> &mut Self {
+        self.join = join;
+        self
+    }
+
+    ///Set the cap style used by strokes after this call.
+    pub fn with_cap(&mut self, cap: CapStyle) -> &mut Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn save(&mut self, _sys: &mut SimpleCanvas) -> PathSave {
+        PathSave {
+            _ns: ns(),
+            buffer: vbo::StaticBuffer::new(&self.verts),
+        }
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        self.verts.append(&mut other.verts);
+    }
+
+    pub fn send_and_uniforms<'a>(&'a mut self, sys: &'a mut SimpleCanvas) -> Uniforms<'a> {
+        sys.circle_buffer.send_to_gpu(&self.verts);
+
+        let common = UniformCommon {
+            color: sys.color,
+            offset: sys.offset,
+        };
+        let un = ProgramUniformValues::new(0.0, gl::TRIANGLES);
+        let buffer = sys.circle_buffer.get_info(self.verts.len());
+        Uniforms {
+            sys,
+            common,
+            un: UniformVals::Regular(un),
+            buffer,
+        }
+    }
+
+    ///Throw away the current path so that a new one can be started.
+    ///Triangles from earlier fills and strokes are kept.
+    pub fn begin_path(&mut self) -> &mut Self {
+        self.subpaths.clear();
+        self
+    }
+
+    ///Start a new subpath at the point.
+    pub fn move_to(&mut self, p: PointType) -> &mut Self {
+        match self.subpaths.last_mut() {
+            //A subpath with a single point draws nothing, so just move it.
+            Some(last) if last.points.len() == 1 && !last.closed => last.points[0] = p,
+            _ => self.subpaths.push(svg::SubPath {
+                points: vec![p],
+                closed: false,
+            }),
+        }
+        self
+    }
+
+    ///The subpath that is being extended, starting one at `p` if there is none.
+    ///A new subpath picks up from the start of the last one if that one was closed.
+    fn current(&mut self, p: PointType) -> &mut Vec<PointType> {
+        let start = match self.subpaths.last() {
+            None => Some(p),
+            Some(last) if last.closed => Some(last.points[0]),
+            Some(_) => None,
+        };
+        if let Some(start) = start {
+            self.subpaths.push(svg::SubPath {
+                points: vec![start],
+                closed: false,
+            });
+        }
+        &mut self.subpaths.last_mut().unwrap().points
+    }
+
+    ///Add a straight line from the current point.
+    pub fn line_to(&mut self, p: PointType) -> &mut Self {
+        self.current(p).push(p);
+        self
+    }
+
+    ///Add a quadratic bezier curve from the current point with control point `c`.
+    pub fn quad_to(&mut self, c: PointType, p: PointType) -> &mut Self {
+        let tolerance = self.tolerance;
+        let points = self.current(c);
+        let p0 = *points.last().unwrap();
+        curve::flatten_quad(p0, c, p, tolerance, points);
+        self
+    }
+
+    ///Add a cubic bezier curve from the current point with control points `c0` and `c1`.
+    pub fn cubic_to(&mut self, c0: PointType, c1: PointType, p: PointType) -> &mut Self {
+        let tolerance = self.tolerance;
+        let points = self.current(c0);
+        let p0 = *points.last().unwrap();
+        curve::flatten_cubic(p0, c0, c1, p, tolerance, points);
+        self
+    }
+
+    ///Add an arc of the given radius that is tangent to the line from the current point to `p1`,
+    ///and to the line from `p1` to `p2`, joined to the current point by a straight line.
+    ///If the lines are parallel or the radius is zero, this is just a line to `p1`.
+    pub fn arc_to(&mut self, p1: PointType, p2: PointType, radius: f32) -> &mut Self {
+        let tolerance = self.tolerance;
+        let points = self.current(p1);
+        let p0 = *points.last().unwrap();
+
+        let norm = |a: PointType, b: PointType| {
+            let d = [a[0] - b[0], a[1] - b[1]];
+            let len = (d[0] * d[0] + d[1] * d[1]).sqrt();
+            [d[0] / len, d[1] / len]
+        };
+        let d0 = norm(p0, p1);
+        let d1 = norm(p2, p1);
+        let cos = d0[0] * d1[0] + d0[1] * d1[1];
+        let sin = d0[0] * d1[1] - d0[1] * d1[0];
+        if radius <= 0.0 || !cos.is_finite() || sin.abs() < 1e-6 {
+            if points.last() != Some(&p1) {
+                points.push(p1);
+            }
+            return self;
+        }
+
+        //The arc touches both lines this far from the corner.
+        let half = cos.acos() * 0.5;
+        let dist = radius / half.tan();
+        let t0 = [p1[0] + d0[0] * dist, p1[1] + d0[1] * dist];
+        let t1 = [p1[0] + d1[0] * dist, p1[1] + d1[1] * dist];
+        let bisect = norm([d0[0] + d1[0], d0[1] + d1[1]], [0.0, 0.0]);
+        let center_dist = radius / half.sin();
+        let center = [
+            p1[0] + bisect[0] * center_dist,
+            p1[1] + bisect[1] * center_dist,
+        ];
+
+        let start = (t0[1] - center[1]).atan2(t0[0] - center[0]);
+        let sweep = core::f32::consts::PI - half * 2.0;
+        let sweep = if sin > 0.0 { -sweep } else { sweep };
+
+        points.push(t0);
+        let n = stroke::arc_segments_within(radius, sweep, tolerance);
+        for i in 1..n {
+            let (s, c) = (start + sweep * (i as f32 / n as f32)).sin_cos();
+            points.push([center[0] + c * radius, center[1] + s * radius]);
+        }
+        points.push(t1);
+        self
+    }
+
+    ///Close the current subpath with a line back to its start.
+    ///Anything added after this starts a new subpath at that same point.
+    pub fn close(&mut self) -> &mut Self {
+        if let Some(last) = self.subpaths.last_mut() {
+            last.closed = true;
+        }
+        self
+    }
+
+    ///Fill the current path. Open subpaths are closed for the fill.
+    pub fn fill(&mut self, rule: FillRule) -> &mut Self {
+        fill_subpaths(&self.subpaths, rule, &mut self.verts);
+        self
+    }
+
+    ///Stroke the current path with the radius of this session, centered on the path.
+    pub fn stroke(&mut self) -> &mut Self {
+        for sub in self.subpaths.iter().filter(|s| s.points.len() >= 2) {
+            stroke::stroke_within(
+                &sub.points,
+                sub.closed,
+                self.radius,
+                self.join,
+                self.cap,
+                self.tolerance,
+                &mut self.verts,
+            );
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(verts: &[circle_program::Vertex]) -> f32 {
+        verts
+            .chunks(3)
+            .map(|t| {
+                let (a, b, c) = (t[0].0, t[1].0, t[2].0);
+                ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])).abs() * 0.5
+            })
+            .sum()
+    }
+
+    fn square(x: f32, size: f32, clockwise: bool) -> svg::SubPath {
+        let mut points = vec![[x, x], [x + size, x], [x + size, x + size], [x, x + size]];
+        if !clockwise {
+            points.reverse();
+        }
+        svg::SubPath {
+            points,
+            closed: true,
+        }
+    }
+
+    #[test]
+    fn arrows_with_no_shaft() {
+        let mut arrows = ArrowSession::new(1.0);
+        arrows.with_head_length(HeadLength::Absolute(20.0));
+        arrows.add([0.0, 0.0], [10.0, 0.0]);
+        arrows.with_head_length(HeadLength::Relative(1.0));
+        arrows.add([0.0, 0.0], [0.0, 10.0]);
+        let styles = [
+            ArrowStyle::DoubleHeaded,
+            ArrowStyle::Open,
+            ArrowStyle::Feathered,
+        ];
+        for &style in styles.iter() {
+            arrows.with_style(style).add([5.0, 5.0], [10.0, 10.0]);
+        }
+        let finite = |v: &circle_program::EdgeVertex| v.pos.iter().all(|a| a.is_finite());
+        assert!(!arrows.verts.is_empty());
+        assert!(arrows.verts.iter().all(finite));
+
+        let before = arrows.verts.len();
+        arrows.add([3.0, 3.0], [3.0, 3.0]);
+        assert_eq!(arrows.verts.len(), before);
+    }
+
+    #[test]
+    fn failed_mesh_adds_change_nothing() {
+        let mut mesh = MeshSession::<u16>::new();
+        mesh.add(&[[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]], &[0, 1, 2]);
+
+        let bad_index = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            mesh.add(&[[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]], &[0, 1, 2, 0, 1, 3]);
+        }));
+        assert!(bad_index.is_err());
+
+        let too_many = vec![[0.0, 0.0]; 65534];
+        let overflow = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            mesh.add(&too_many, &[0, 1, 65533]);
+        }));
+        assert!(overflow.is_err());
+        assert_eq!((mesh.verts.len(), mesh.indices.len()), (3, 3));
+
+        mesh.add(&too_many[1..], &[0, 1, 65532]);
+        assert_eq!(mesh.indices[5], 65535);
+    }
+
+    #[test]
+    fn fill_rules() {
+        let fill = |subpaths: &[svg::SubPath], rule| {
+            let mut out = Vec::new();
+            fill_subpaths(subpaths, rule, &mut out);
+            area(&out)
+        };
+        let same = [square(0.0, 10.0, true), square(2.0, 6.0, true)];
+        let opposite = [square(0.0, 10.0, true), square(2.0, 6.0, false)];
+
+        assert!((fill(&same, FillRule::NonZero) - 100.0).abs() < 1e-3);
+        assert!((fill(&same, FillRule::EvenOdd) - 64.0).abs() < 1e-3);
+        assert!((fill(&opposite, FillRule::NonZero) - 64.0).abs() < 1e-3);
+        assert!((fill(&opposite, FillRule::EvenOdd) - 64.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn regular_polygon_uvs() {
+        let mut hexes = RegularPolygonSession::new();
+        hexes.add([10.0, 10.0], 5.0, 6, 0.0);
+        hexes.add([10.0, 10.0], 5.0, 6, core::f32::consts::PI * 0.5);
+        assert_eq!(hexes.verts.len(), 36);
+
+        //The first corner points up at the top of the square around the hex,
+        //and stays there in the texture when the hex is turned.
+        let (center, first) = (hexes.verts[0], hexes.verts[1]);
+        assert_eq!(center.pos, [10.0, 10.0]);
+        assert_eq!(center.uv, [0.5, 0.5]);
+        assert!((first.pos[0] - 10.0).abs() < 1e-5 && (first.pos[1] - 5.0).abs() < 1e-5);
+        assert!((first.uv[0] - 0.5).abs() < 1e-5 && first.uv[1].abs() < 1e-5);
+
+        let turned = hexes.verts[19];
+        assert!((turned.pos[0] - 15.0).abs() < 1e-5 && (turned.pos[1] - 10.0).abs() < 1e-5);
+        assert!((turned.uv[0] - 0.5).abs() < 1e-5 && turned.uv[1].abs() < 1e-5);
+
+        //The texture covers the corners from side to side and does not go past them.
+        for v in hexes.verts.iter() {
+            let offset = [v.pos[0] - 10.0, v.pos[1] - 10.0];
+            let from_center = (offset[0] * offset[0] + offset[1] * offset[1]).sqrt();
+            let uv_from_center = ((v.uv[0] - 0.5).powi(2) + (v.uv[1] - 0.5).powi(2)).sqrt();
+            assert!((uv_from_center * 10.0 - from_center).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn polygon_uvs() {
+        let mut polygons = PolygonSession::new();
+        polygons.add(&[[2.0, 4.0], [12.0, 4.0], [12.0, 9.0], [7.0, 6.0], [2.0, 9.0]]);
+        assert!(!polygons.verts.is_empty());
+        for v in polygons.verts.iter() {
+            let expected = [(v.pos[0] - 2.0) / 10.0, (v.pos[1] - 4.0) / 5.0];
+            assert!((v.uv[0] - expected[0]).abs() < 1e-5 && (v.uv[1] - expected[1]).abs() < 1e-5);
+        }
+        assert!(polygons.verts.iter().any(|v| v.uv == [1.0, 1.0]));
+
+        //Bounds with no height still give finite uvs.
+        let (mut flat, point) = (Vec::new(), circle_program::Vertex([1.0, 0.0]));
+        push_with_uvs(&[point], [0.0, 2.0, 0.0, 0.0], &mut flat);
+        assert_eq!(flat[0].uv, [0.5, 0.0]);
+    }
+
+    #[test]
+    fn rounded_rect_uvs() {
+        let mut rects = RoundedRectSession::new(3.0, 1.0, 0.1);
+        rects.add([10.0, 0.0, 0.0, 20.0]);
+        rects.add_outline([0.0, 10.0, 0.0, 20.0]);
+        assert_eq!(rects.verts[0].uv, [0.5, 0.5]);
+        for v in rects.verts.iter() {
+            assert!((v.uv[0] * 10.0 - v.pos[0]).abs() < 1e-4);
+            assert!((v.uv[1] * 20.0 - v.pos[1]).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn arcs_with_bad_angles() {
+        let mut arcs = ArcSession::new(1.0, 0.1);
+        let bad = [core::f32::NAN, core::f32::INFINITY, core::f32::NEG_INFINITY];
+        for &a in bad.iter() {
+            arcs.add([0.0, 0.0], 10.0, 0.0, a);
+            arcs.add([0.0, 0.0], 10.0, a, 1.0);
+            arcs.add_sector([0.0, 0.0], 10.0, 0.0, a);
+            arcs.add_sector([0.0, 0.0], 10.0, a, 1.0);
+        }
+        assert!(arcs.verts.is_empty());
+
+        arcs.add([0.0, 0.0], 10.0, 0.0, 100.0);
+        assert!(!arcs.verts.is_empty());
+    }
 }
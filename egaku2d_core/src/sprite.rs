@@ -5,6 +5,7 @@ use super::*;
 pub struct SpriteSave {
     _ns: NotSend,
     pub(crate) buffer: vbo::StaticBuffer<sprite_program::Vertex>,
+    max_scale: f32,
 }
 impl SpriteSave {
     pub fn uniforms<'a>(
@@ -20,7 +21,12 @@ impl SpriteSave {
             color: sys.color,
             offset: sys.offset,
         };
-        let un = SpriteProgramUniformValues { radius, texture };
+        let un = SpriteProgramUniformValues {
+            radius,
+            max_scale: self.max_scale,
+            texture,
+            quad: false,
+        };
         Uniforms {
             sys,
             common,
@@ -30,25 +36,74 @@ impl SpriteSave {
     }
 }
 
+///The biggest scale of any of the sprites.
+fn max_scale(verts: &[sprite_program::Vertex]) -> f32 {
+    let max = verts.iter().fold(0, |acc, v| {
+        let scale = v.scale;
+        acc.max(scale[0]).max(scale[1])
+    });
+    sprite_program::unpack_scale(max)
+}
+
 pub struct SpriteSession {
+    pub(crate) scale: [u16; 2],
+    pub(crate) flip: u16,
+    pub(crate) tint: [u8; 4],
     pub(crate) verts: Vec<sprite_program::Vertex>,
 }
 
 impl SpriteSession {
     pub fn new() -> Self {
-        SpriteSession { verts: Vec::new() }
+        let one = sprite_program::pack_scale(1.0);
+        SpriteSession {
+            scale: [one, one],
+            flip: 0,
+            tint: [255; 4],
+            verts: Vec::new(),
+        }
+    }
+
+    ///Set how much sprites added after this call are stretched along their own x and y axis.
+    ///The radius passed when drawing is the radius of a sprite with a scale of 1.
+    ///Scales are stored with a precision of 1/256 and can be at most 255.
+    pub fn with_scale(&mut self, scale: [f32; 2]) -> &mut Self {
+        self.scale = [
+            sprite_program::pack_scale(scale[0]),
+            sprite_program::pack_scale(scale[1]),
+        ];
+        self
+    }
+
+    ///Set whether sprites added after this call are mirrored horizontally and vertically.
+    ///The sprite is mirrored along its own axis, so a rotated sprite is mirrored before it is rotated.
+    pub fn with_flip(&mut self, flip_x: bool, flip_y: bool) -> &mut Self {
+        self.flip = 0;
+        if flip_x {
+            self.flip |= sprite_program::FLIP_X;
+        }
+        if flip_y {
+            self.flip |= sprite_program::FLIP_Y;
+        }
+        self
     }
+
+    ///Set the color that sprites added after this call are multiplied by,
+    ///on top of the color of the whole draw. Sprites are not tinted by default.
+    ///Each channel is stored in a byte.
+    pub fn with_tint(&mut self, color: [f32; 4]) -> &mut Self {
+        self.tint = circle_program::pack_color(color);
+        self
+    }
+
     ///Add a point sprite.
     #[inline(always)]
     pub fn add(&mut self, point: PointType, index: u16, rotation: f32) -> &mut Self {
-        let k = rotation.rem_euclid(core::f32::consts::PI * 2.);
-        let k = k / (core::f32::consts::PI * 2.);
-        let k = (k * (core::u16::MAX as f32)) as u16;
-
         self.verts.push(sprite_program::Vertex {
             pos: point,
             index: index as u16,
-            rotation: k,
+            rotation: sprite_program::pack_rotation(rotation, self.flip),
+            scale: self.scale,
+            tint: self.tint,
         });
         self
     }
@@ -62,6 +117,7 @@ impl SpriteSession {
         SpriteSave {
             _ns: ns(),
             buffer: vbo::StaticBuffer::new(&self.verts),
+            max_scale: max_scale(&self.verts),
         }
     }
 
@@ -80,7 +136,12 @@ impl SpriteSession {
             color: sys.color,
             offset: sys.offset,
         };
-        let un = SpriteProgramUniformValues { radius, texture };
+        let un = SpriteProgramUniformValues {
+            radius,
+            max_scale: max_scale(&self.verts),
+            texture,
+            quad: false,
+        };
 
         let buffer = sys.sprite_buffer.get_info(self.verts.len());
         Uniforms {
@@ -92,11 +153,49 @@ impl SpriteSession {
     }
 }
 
+///What a texture on a shape is pinned to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TextureSpace {
+    ///The texture is fixed to the screen and shapes slide over it.
+    Screen,
+    ///The texture is fixed to the same coordinates as the vertices,
+    ///so it moves along with `with_offset`.
+    World,
+    ///The texture is stretched over each circle, square and rect (including rotated rects and quads),
+    ///so it moves and rotates along with the shape.
+    ///Regular polygons and stars are covered by the square around their outer radius.
+    ///Polygons are covered by the smallest rect around their outline, and rounded rects by their rect.
+    ///Every other shape falls back to `World`.
+    Local,
+}
+
+///What happens when a texture is sampled outside of its bounds.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WrapMode {
+    ///Tile the texture.
+    Repeat,
+    ///Tile the texture, flipping every other tile.
+    MirroredRepeat,
+    ///Stretch the pixels on the border of the texture.
+    Clamp,
+}
+
+impl WrapMode {
+    fn gl_enum(self) -> GLenum {
+        match self {
+            WrapMode::Repeat => gl::REPEAT,
+            WrapMode::MirroredRepeat => gl::MIRRORED_REPEAT,
+            WrapMode::Clamp => gl::CLAMP_TO_EDGE,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Texture {
     _ns: NotSend,
     pub(crate) grid_dim: [u8; 2],
     pub(crate) dim: [f32; 2],
+    pub(crate) padding: f32,
     pub(crate) id: GLuint,
 }
 
@@ -107,6 +206,28 @@ impl Texture {
     pub fn dim(&self) -> [f32; 2] {
         self.dim
     }
+    ///How many pixels along each side of a tile are left out when it is drawn as a sprite.
+    pub fn padding(&self) -> f32 {
+        self.padding
+    }
+    ///Set how the texture wraps along the x and y axis when it is drawn on shapes.
+    ///Textures repeat along both axis by default.
+    pub fn set_wrap_mode(&mut self, x: WrapMode, y: WrapMode) {
+        unsafe {
+            gl::BindTexture(gl::TEXTURE_2D, self.id);
+            gl_ok!();
+
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, x.gl_enum() as i32);
+            gl_ok!();
+
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, y.gl_enum() as i32);
+            gl_ok!();
+
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+            gl_ok!();
+        }
+    }
+
     ///Create a texture index from a coordinate in the tile set.
     ///The top left time maps to 0,0.
     ///The x component grows to the right.
@@ -118,11 +239,23 @@ impl Texture {
     }
 
     pub unsafe fn new(textureid: GLuint, grid_dim: [u8; 2], dim: [f32; 2]) -> Texture {
+        Self::new_with_padding(textureid, grid_dim, dim, 0.0)
+    }
+
+    ///Create a tile set where each tile has a border of `padding` pixels around it
+    ///that is not drawn as part of the sprite, such as the extruded edges of a texture atlas.
+    pub unsafe fn new_with_padding(
+        textureid: GLuint,
+        grid_dim: [u8; 2],
+        dim: [f32; 2],
+        padding: f32,
+    ) -> Texture {
         Texture {
             id: textureid,
             grid_dim,
             _ns: ns(),
             dim,
+            padding,
         }
     }
 }
@@ -10,11 +10,16 @@ use crate::vbo::BufferInfo;
 static VS_SRC: &'static str = "
 #version 300 es
 in vec2 position;
-in float rotation;
+in uint rotation;
 in uint cellindex;
+in vec2 scale;
+in vec4 tint;
 
 out vec2 texture_offset;
+out vec4 vtint;
+out vec2 rel_scale;
 out mat2 rot_matrix;
+out vec2 quad_coord;
 
 uniform vec2 offset;
 uniform ivec2 grid_dim;
@@ -23,15 +28,47 @@ uniform vec2 sprite_dim;
 
 uniform mat3 mmatrix;
 uniform float point_size;
+uniform bool quad;
+uniform float point_mul;
+
+//The corners of the two triangles drawn in place of a point that is too big.
+const vec2 CORNERS[6]=vec2[6](
+    vec2(0.0,0.0),vec2(1.0,0.0),vec2(0.0,1.0),
+    vec2(0.0,1.0),vec2(1.0,0.0),vec2(1.0,1.0)
+);
 
 const float PI = 3.1415926535897932384626433832795;
 
 void main() {
-    gl_PointSize = point_size;
-    vec3 pp = vec3(position.xy+offset,1.0);
+    //The scale is fixed point with 8 fractional bits.
+    //The point has to be big enough for the larger side of the scaled sprite.
+    vec2 sc=scale*(1.0/256.0);
+    float smax=max(sc.x,sc.y);
+    gl_PointSize = point_size*smax;
+    rel_scale=max(sc/max(smax,0.0001),vec2(0.0001,0.0001));
+
+    //A negative scale mirrors the sprite along that axis.
+    if((rotation&1u)!=0u){
+        rel_scale.x=-rel_scale.x;
+    }
+    if((rotation&2u)!=0u){
+        rel_scale.y=-rel_scale.y;
+    }
+
+    vtint=tint;
+
+    vec2 p=position+offset;
+    quad_coord=vec2(0.0,0.0);
+    if(quad){
+        //Each instance is a quad the size of the point it replaces.
+        quad_coord=CORNERS[gl_VertexID];
+        p+=(quad_coord-vec2(0.5,0.5))*gl_PointSize/point_mul;
+    }
+    vec3 pp = vec3(p,1.0);
     gl_Position = vec4(mmatrix*pp.xyz, 1.0);
 
-    float rot=rotation*(PI*2.0);
+    //The lowest two bits of the rotation are the flip flags.
+    float rot=float(rotation>>2u)*(PI*2.0/16384.0);
     float c=cos(rot);
     float s=sin(rot);
 
@@ -54,8 +91,13 @@ static FS_SRC: &'static str = "
 precision mediump float;
 in vec2 texture_offset;
 in mat2 rot_matrix;
+in vec2 quad_coord;
+in vec2 rel_scale;
+in vec4 vtint;
+uniform bool quad;
 uniform highp ivec2 grid_dim;
 uniform highp vec2 sprite_dim;
+uniform highp vec2 cell_inset;
 uniform sampler2D tex0;
 uniform vec4 bcol;
 out vec4 out_color;
@@ -75,7 +117,9 @@ void main()
     mat2 grid_dim2=mat2(1.0/dim.x,0.0,0.0,1.0/dim.y);
     
     //Handle rotation before we do anything.`
-    vec2 pos=  (rot_matrix*( (gl_PointCoord.xy-mid)) + mid);
+    vec2 point_coord = quad ? quad_coord : gl_PointCoord;
+    //Then stretch the sprite along its own axis to its scale within the point.
+    vec2 pos=  (rot_matrix*( (point_coord-mid))/rel_scale + mid);
     
     vec2 extra=vec2(max(0.0,(sprite_dim.y-sprite_dim.x)/3.0),max(0.0,(sprite_dim.x-sprite_dim.y)/3.0)) ;
     extra.x+=0.01; //TODO why is this needed?
@@ -97,9 +141,12 @@ void main()
                 
 
 
+        //Skip over the padding around the tile.
+        pp2=pp2*(1.0-2.0*cell_inset)+cell_inset;
+
         vec2 foo =  (pp2+ texture_offset)*grid_dim2;
 
-        out_color=texture(tex0,foo)*bcol;
+        out_color=texture(tex0,foo)*bcol*vtint;
     }
 }
 ";
@@ -109,7 +156,33 @@ void main()
 pub struct Vertex {
     pub pos: [f32; 2], //TODO use half floats??
     pub index: u16,
-    pub rotation: u16,
+    pub rotation: u16, //the lowest two bits are the flip flags
+    pub scale: [u16; 2], //fixed point with 8 fractional bits
+    pub tint: [u8; 4],
+}
+
+///Flag in the rotation of a sprite vertex that mirrors it horizontally.
+pub const FLIP_X: u16 = 1;
+///Flag in the rotation of a sprite vertex that mirrors it vertically.
+pub const FLIP_Y: u16 = 2;
+
+///Convert a rotation in radians to the format stored in a sprite vertex,
+///leaving the lowest two bits for the flip flags.
+pub fn pack_rotation(rotation: f32, flags: u16) -> u16 {
+    let k = rotation.rem_euclid(core::f32::consts::PI * 2.);
+    let k = k / (core::f32::consts::PI * 2.);
+    let k = (k * 16384.0) as u16 % 16384;
+    (k << 2) | (flags & (FLIP_X | FLIP_Y))
+}
+
+///Convert a scale to the fixed point format stored in a sprite vertex.
+pub fn pack_scale(scale: f32) -> u16 {
+    (scale * 256.0).round().max(0.0).min(core::u16::MAX as f32) as u16
+}
+
+///Convert a scale stored in a sprite vertex back to a float.
+pub fn unpack_scale(scale: u16) -> f32 {
+    scale as f32 / 256.0
 }
 
 #[derive(Debug)]
@@ -121,10 +194,15 @@ pub struct SpriteProgram {
     pub point_size_uniform: GLint,
     pub grid_dim_uniform: GLint,
     pub sprite_dim_uniform: GLint,
+    pub cell_inset_uniform: GLint,
     pub bcol_uniform: GLint,
+    pub quad_uniform: GLint,
+    pub point_mul_uniform: GLint,
     pub pos_attr: GLint,
     pub rotation_attr: GLint,
     pub index_attr: GLint,
+    pub scale_attr: GLint,
+    pub tint_attr: GLint,
     pub sample_location: GLint,
 }
 
@@ -135,6 +213,10 @@ pub struct PointMul(pub f32);
 pub struct SpriteProgramUniformValues<'a> {
     pub texture: &'a crate::sprite::Texture,
     pub radius: f32,
+    ///The biggest scale of any sprite in the buffer.
+    pub max_scale: f32,
+    ///Draw each sprite as a quad instead, for sprites bigger than the driver can draw.
+    pub quad: bool,
 }
 
 impl SpriteProgram {
@@ -153,6 +235,8 @@ impl SpriteProgram {
 
         let matrix = [[scalex, 0.0, 0.0], [0.0, -scaley, 0.0], [tx, ty, 1.0]];
 
+        let point_mul = window_dim.width as f32 / game_width;
+
         unsafe {
             gl::UseProgram(self.program);
             gl_ok!();
@@ -163,9 +247,11 @@ impl SpriteProgram {
                 std::mem::transmute(&matrix[0][0]),
             );
             gl_ok!();
+            gl::Uniform1f(self.point_mul_uniform, point_mul);
+            gl_ok!();
         }
 
-        PointMul(window_dim.width as f32 / game_width)
+        PointMul(point_mul)
     }
 
     pub(crate) fn set_buffer_and_draw(
@@ -196,6 +282,9 @@ impl SpriteProgram {
             gl::Uniform4fv(self.bcol_uniform, 1, col.as_ptr() as *const _);
             gl_ok!();
 
+            gl::Uniform1i(self.quad_uniform, un.quad as i32);
+            gl_ok!();
+
             gl::BindBuffer(gl::ARRAY_BUFFER, buffer_id);
             gl_ok!();
 
@@ -208,10 +297,17 @@ impl SpriteProgram {
             gl::Uniform1i(self.sample_location, 0);
             gl_ok!();
 
-            assert_eq!(core::mem::size_of::<Vertex>(), 4 * 3);
+            assert_eq!(core::mem::size_of::<Vertex>(), 4 * 5);
+
+            let cx = texture.dim[0] / (texture.grid_dim[0] as f32);
+            let cy = texture.dim[1] / (texture.grid_dim[1] as f32);
+            let pad = texture.padding;
+
+            gl::Uniform2f(self.cell_inset_uniform, pad / cx, pad / cy);
+            gl_ok!();
 
-            let sx = texture.dim[0] / (texture.grid_dim[0] as f32);
-            let sy = texture.dim[1] / (texture.grid_dim[1] as f32);
+            let sx = cx - 2.0 * pad;
+            let sy = cy - 2.0 * pad;
 
             let sprite_dim = if sx > sy {
                 [1.0, sy / sx]
@@ -239,7 +335,7 @@ impl SpriteProgram {
                 2,
                 gl::FLOAT,
                 gl::FALSE as GLboolean,
-                3 * 4 as i32,
+                4 * 5 as i32,
                 0 as *const _,
             );
             gl_ok!();
@@ -251,7 +347,7 @@ impl SpriteProgram {
                 self.index_attr as GLuint,
                 1,
                 gl::UNSIGNED_SHORT,
-                (3 * 4) as i32,
+                (4 * 5) as i32,
                 (4 * 2) as *const _,
             );
             gl_ok!();
@@ -259,26 +355,70 @@ impl SpriteProgram {
             gl::EnableVertexAttribArray(self.rotation_attr as GLuint);
             gl_ok!();
 
-            gl::VertexAttribPointer(
+            gl::VertexAttribIPointer(
                 self.rotation_attr as GLuint,
                 1,
                 gl::UNSIGNED_SHORT,
-                gl::TRUE,
-                3 * 4 as i32,
+                (4 * 5) as i32,
                 ((4 * 2) + 2) as *const _,
             );
             gl_ok!();
 
-            gl::DrawArrays(mode, 0 as i32, length as i32);
+            gl::EnableVertexAttribArray(self.scale_attr as GLuint);
+            gl_ok!();
 
+            gl::VertexAttribPointer(
+                self.scale_attr as GLuint,
+                2,
+                gl::UNSIGNED_SHORT,
+                gl::FALSE as GLboolean,
+                4 * 5 as i32,
+                (4 * 3) as *const _,
+            );
             gl_ok!();
 
+            gl::EnableVertexAttribArray(self.tint_attr as GLuint);
+            gl_ok!();
+
+            gl::VertexAttribPointer(
+                self.tint_attr as GLuint,
+                4,
+                gl::UNSIGNED_BYTE,
+                gl::TRUE,
+                4 * 5 as i32,
+                (4 * 4) as *const _,
+            );
+            gl_ok!();
+
+            if un.quad {
+                let attrs = [
+                    self.pos_attr,
+                    self.index_attr,
+                    self.rotation_attr,
+                    self.scale_attr,
+                    self.tint_attr,
+                ];
+                vbo::set_divisors(&attrs, 1);
+                vbo::draw_point_quads(buffer_info);
+                vbo::set_divisors(&attrs, 0);
+            } else {
+                gl::DrawArrays(mode, 0 as i32, length as i32);
+
+                gl_ok!();
+            }
+
             gl::DisableVertexAttribArray(self.pos_attr as GLuint);
             gl_ok!();
 
             gl::DisableVertexAttribArray(self.index_attr as GLuint);
             gl_ok!();
 
+            gl::DisableVertexAttribArray(self.scale_attr as GLuint);
+            gl_ok!();
+
+            gl::DisableVertexAttribArray(self.tint_attr as GLuint);
+            gl_ok!();
+
             gl::BindBuffer(gl::ARRAY_BUFFER, 0);
             gl_ok!();
 
@@ -318,6 +458,11 @@ impl SpriteProgram {
                 gl::GetUniformLocation(program, temp.as_ptr());
             gl_ok!();
 
+            let temp=CString::new("cell_inset").unwrap();
+            let cell_inset_uniform: GLint =
+                gl::GetUniformLocation(program, temp.as_ptr());
+            gl_ok!();
+
             let temp=CString::new("square").unwrap();
             let square_uniform: GLint =
                 gl::GetUniformLocation(program, temp.as_ptr());
@@ -338,6 +483,16 @@ impl SpriteProgram {
                 gl::GetUniformLocation(program, temp.as_ptr());
             gl_ok!();
 
+            let temp=CString::new("quad").unwrap();
+            let quad_uniform: GLint =
+                gl::GetUniformLocation(program, temp.as_ptr());
+            gl_ok!();
+
+            let temp=CString::new("point_mul").unwrap();
+            let point_mul_uniform: GLint =
+                gl::GetUniformLocation(program, temp.as_ptr());
+            gl_ok!();
+
             let temp=CString::new("offset").unwrap();
             let offset_uniform: GLint =
                 gl::GetUniformLocation(program, temp.as_ptr());
@@ -358,6 +513,16 @@ impl SpriteProgram {
                 gl::GetAttribLocation(program, temp.as_ptr());
             gl_ok!();
 
+            let temp=CString::new("scale").unwrap();
+            let scale_attr =
+                gl::GetAttribLocation(program, temp.as_ptr());
+            gl_ok!();
+
+            let temp=CString::new("tint").unwrap();
+            let tint_attr =
+                gl::GetAttribLocation(program, temp.as_ptr());
+            gl_ok!();
+
             let temp=CString::new("tex0").unwrap();
             let sample_location =
                 gl::GetAttribLocation(program, temp.as_ptr());
@@ -372,10 +537,15 @@ impl SpriteProgram {
                 point_size_uniform,
                 grid_dim_uniform,
                 sprite_dim_uniform,
+                cell_inset_uniform,
                 matrix_uniform,
                 bcol_uniform,
+                quad_uniform,
+                point_mul_uniform,
                 pos_attr,
                 index_attr,
+                scale_attr,
+                tint_attr,
             }
         }
     }
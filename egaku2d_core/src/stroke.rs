@@ -0,0 +1,473 @@
+//! Turns polylines into one continuous triangle strip worth of triangles.
+//!
+//! Each segment is a quad. On the inside of a corner the two quads share the point
+//! where their inner edges meet so they do not overlap, and the gap on the outside
+//! of the corner is filled in according to the join style.
+
+use super::*;
+use crate::shapes::{CapStyle, JoinStyle};
+
+type P = [f32; 2];
+
+#[inline(always)]
+fn dot(a: Vec2<f32>, b: Vec2<f32>) -> f32 {
+    a.x * b.x + a.y * b.y
+}
+
+#[inline(always)]
+fn cross(a: Vec2<f32>, b: Vec2<f32>) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+#[inline(always)]
+fn length(a: Vec2<f32>) -> f32 {
+    dot(a, a).sqrt()
+}
+
+#[inline(always)]
+fn perp(a: Vec2<f32>) -> Vec2<f32> {
+    vec2(-a.y, a.x)
+}
+
+#[inline(always)]
+fn rotate(a: Vec2<f32>, angle: f32) -> Vec2<f32> {
+    let (s, c) = angle.sin_cos();
+    vec2(a.x * c - a.y * s, a.x * s + a.y * c)
+}
+
+#[inline(always)]
+fn tri(out: &mut Vec<circle_program::Vertex>, a: Vec2<f32>, b: Vec2<f32>, c: Vec2<f32>) {
+    out.push(circle_program::Vertex([a.x, a.y]));
+    out.push(circle_program::Vertex([b.x, b.y]));
+    out.push(circle_program::Vertex([c.x, c.y]));
+}
+
+///How many pieces to split an arc of the given radius and angle into
+///so that it never strays more than `tolerance` from the true arc.
+///A f32 can not place points much closer to the arc than a millionth of its radius,
+///so smaller tolerances are raised to that, which keeps a full circle to a few thousand pieces.
+pub fn arc_segments_within(radius: f32, angle: f32, tolerance: f32) -> usize {
+    let tolerance = tolerance.max(radius * 1e-6);
+    let step = if radius > tolerance {
+        //The same as 2*acos(1-tolerance/radius), without losing the small difference to rounding.
+        4.0 * (tolerance / (2.0 * radius)).sqrt().asin()
+    } else {
+        angle
+    };
+    ((angle.abs() / step).ceil() as usize).max(1)
+}
+
+///Fan out an arc around `center` starting at `center+start` and sweeping `angle` radians.
+fn fan(
+    out: &mut Vec<circle_program::Vertex>,
+    pivot: Vec2<f32>,
+    center: Vec2<f32>,
+    start: Vec2<f32>,
+    angle: f32,
+    tolerance: f32,
+) {
+    let n = arc_segments_within(length(start), angle, tolerance);
+    let mut prev = center + start;
+    for i in 1..=n {
+        let next = center + rotate(start, angle * (i as f32 / n as f32));
+        tri(out, pivot, prev, next);
+        prev = next;
+    }
+}
+
+///The left and right edge points of a segment where it meets a joint.
+#[derive(Copy, Clone)]
+struct Edge {
+    left: Vec2<f32>,
+    right: Vec2<f32>,
+}
+
+///Work out where the incoming and outgoing segments end and start at the corner `p`,
+///and push the triangles that fill the outside of the corner.
+fn joint(
+    out: &mut Vec<circle_program::Vertex>,
+    prev: Vec2<f32>,
+    p: Vec2<f32>,
+    next: Vec2<f32>,
+    radius: f32,
+    join: JoinStyle,
+    tolerance: f32,
+) -> (Edge, Edge) {
+    let l0 = length(p - prev);
+    let l1 = length(next - p);
+    let d0 = (p - prev) / l0;
+    let d1 = (next - p) / l1;
+    let n0 = perp(d0);
+    let n1 = perp(d1);
+
+    let turn = cross(d0, d1);
+    if turn.abs() < 1e-6 && dot(d0, d1) > 0.0 {
+        //Straight through, nothing to fill in.
+        let e = Edge {
+            left: p + n0 * radius,
+            right: p - n0 * radius,
+        };
+        return (e, e);
+    }
+
+    //The side of the line the corner turns towards.
+    let inner = if turn >= 0.0 { 1.0 } else { -1.0 };
+
+    let outer0 = p - n0 * (inner * radius);
+    let outer1 = p - n1 * (inner * radius);
+
+    let sum = n0 + n1;
+    let sum_len = length(sum);
+
+    //Distance from the corner to where the offset edges meet, and how far along
+    //the segments that point is. If it is further than either segment is long,
+    //the segments are too short to share an inner point.
+    let (miter, miter_len) = if sum_len > 1e-6 {
+        let m = sum / sum_len;
+        let len = radius / dot(m, n0);
+        (Some(m), len)
+    } else {
+        (None, core::f32::INFINITY)
+    };
+    let along = miter_len * (1.0 - dot(miter.unwrap_or(n0), n0).powi(2)).max(0.0).sqrt();
+
+    let (pivot, inner0, inner1) = match miter {
+        Some(m) if along <= l0.min(l1) => {
+            let ip = p + m * (inner * miter_len);
+            (ip, ip, ip)
+        }
+        _ => (p, p + n0 * (inner * radius), p + n1 * (inner * radius)),
+    };
+
+    match join {
+        JoinStyle::Bevel => tri(out, pivot, outer0, outer1),
+        JoinStyle::Miter { limit } => match miter {
+            Some(m) if miter_len <= limit * radius => {
+                let tip = p - m * (inner * miter_len);
+                tri(out, pivot, outer0, tip);
+                tri(out, pivot, tip, outer1);
+            }
+            _ => tri(out, pivot, outer0, outer1),
+        },
+        JoinStyle::Round => {
+            let start = outer0 - p;
+            let end = outer1 - p;
+            //When the path doubles back on itself the sign of the angle is unreliable,
+            //so go around the front of the incoming segment.
+            let angle = if turn.abs() < 1e-6 {
+                core::f32::consts::PI * inner
+            } else {
+                cross(start, end).atan2(dot(start, end))
+            };
+            fan(out, pivot, p, start, angle, tolerance);
+        }
+    }
+
+    let (e0, e1) = if inner > 0.0 {
+        (
+            Edge {
+                left: inner0,
+                right: outer0,
+            },
+            Edge {
+                left: inner1,
+                right: outer1,
+            },
+        )
+    } else {
+        (
+            Edge {
+                left: outer0,
+                right: inner0,
+            },
+            Edge {
+                left: outer1,
+                right: inner1,
+            },
+        )
+    };
+    (e0, e1)
+}
+
+///Build the edge at an open end of the path and push any cap triangles.
+///`dir` points away from the path.
+fn cap_end(
+    out: &mut Vec<circle_program::Vertex>,
+    p: Vec2<f32>,
+    dir: Vec2<f32>,
+    radius: f32,
+    cap: CapStyle,
+    tolerance: f32,
+) -> Edge {
+    let dir = dir / length(dir);
+    //Pointing along the path so that left and right agree with the rest of the path.
+    let n = perp(-dir);
+    match cap {
+        CapStyle::Butt => Edge {
+            left: p + n * radius,
+            right: p - n * radius,
+        },
+        CapStyle::Square => {
+            let p = p + dir * radius;
+            Edge {
+                left: p + n * radius,
+                right: p - n * radius,
+            }
+        }
+        CapStyle::Round => {
+            let left = n * radius;
+            let angle = if cross(left, dir) > 0.0 {
+                core::f32::consts::PI
+            } else {
+                -core::f32::consts::PI
+            };
+            fan(out, p, p, left, angle, tolerance);
+            Edge {
+                left: p + left,
+                right: p - left,
+            }
+        }
+    }
+}
+
+#[inline(always)]
+fn quad(out: &mut Vec<circle_program::Vertex>, start: Edge, end: Edge) {
+    tri(out, start.left, start.right, end.left);
+    tri(out, start.right, end.right, end.left);
+}
+
+///Stroke the path with a line of the given radius, pushing the triangles onto `out`.
+///Round joins and caps stay within `tolerance` of true arcs.
+pub fn stroke_within(
+    points: &[P],
+    closed: bool,
+    radius: f32,
+    join: JoinStyle,
+    cap: CapStyle,
+    tolerance: f32,
+    out: &mut Vec<circle_program::Vertex>,
+) {
+    let mut pts: Vec<P> = Vec::with_capacity(points.len());
+    for &p in points.iter() {
+        if pts.last() != Some(&p) {
+            pts.push(p);
+        }
+    }
+    if closed {
+        while pts.len() > 1 && pts.first() == pts.last() {
+            pts.pop();
+        }
+    }
+    let pts: Vec<Vec2<f32>> = pts.iter().map(|p| vec2(p[0], p[1])).collect();
+
+    match pts.len() {
+        0 => return,
+        1 => {
+            //A lone point only shows up if it has a cap.
+            let p = pts[0];
+            match cap {
+                CapStyle::Butt => {}
+                CapStyle::Square => {
+                    let a = p + vec2(-radius, -radius);
+                    let b = p + vec2(radius, -radius);
+                    let c = p + vec2(radius, radius);
+                    let d = p + vec2(-radius, radius);
+                    tri(out, a, b, c);
+                    tri(out, c, d, a);
+                }
+                CapStyle::Round => {
+                    let angle = core::f32::consts::PI * 2.0;
+                    fan(out, p, p, vec2(radius, 0.0), angle, tolerance)
+                }
+            }
+            return;
+        }
+        2 if closed => return stroke_within(points, false, radius, join, cap, tolerance, out),
+        _ => {}
+    }
+
+    let n = pts.len();
+    if closed {
+        let joints: Vec<(Edge, Edge)> = (0..n)
+            .map(|i| {
+                let (prev, next) = (pts[(i + n - 1) % n], pts[(i + 1) % n]);
+                joint(out, prev, pts[i], next, radius, join, tolerance)
+            })
+            .collect();
+        for i in 0..n {
+            quad(out, joints[i].1, joints[(i + 1) % n].0);
+        }
+    } else {
+        let first = cap_end(out, pts[0], pts[0] - pts[1], radius, cap, tolerance);
+        let last = cap_end(
+            out,
+            pts[n - 1],
+            pts[n - 1] - pts[n - 2],
+            radius,
+            cap,
+            tolerance,
+        );
+        //The end cap was built facing away from the path, so its sides are swapped.
+        let last = Edge {
+            left: last.right,
+            right: last.left,
+        };
+
+        let mut start = first;
+        for i in 1..n - 1 {
+            let (end, next) = joint(out, pts[i - 1], pts[i], pts[i + 1], radius, join, tolerance);
+            quad(out, start, end);
+            start = next;
+        }
+        quad(out, start, last);
+    }
+}
+
+///Split a path into the pieces that are "on" in the dash pattern.
+///The pattern is measured continuously along the whole path starting `phase` units into it.
+///A pattern with an odd number of lengths is repeated so that it has an even number.
+///The dash that runs into the end of a closed path carries on into the one it started with.
+///Returns None if the path should be drawn solid instead: when the pattern has no length,
+///when it is too fine to be measured out along the longest segment in a f32,
+///or when a closed path never gets out of its first dash.
+pub fn dash(points: &[P], closed: bool, pattern: &[f32], phase: f32) -> Option<Vec<Vec<P>>> {
+    let mut path = points.to_vec();
+    if closed {
+        if let Some(&first) = points.first() {
+            path.push(first);
+        }
+    }
+
+    let mut pattern: Vec<f32> = pattern.iter().map(|&a| a.max(0.0)).collect();
+    if pattern.len() % 2 == 1 {
+        let copy = pattern.clone();
+        pattern.extend(copy);
+    }
+    let total: f32 = pattern.iter().sum();
+    if path.is_empty() || !(total > 0.0 && total.is_finite()) {
+        return None;
+    }
+
+    //Stepping along a segment stops making progress once the steps are smaller than
+    //the precision of the distance travelled, and would make millions of dashes before that.
+    let longest = path
+        .windows(2)
+        .map(|w| ((w[1][0] - w[0][0]).powi(2) + (w[1][1] - w[0][1]).powi(2)).sqrt())
+        .fold(0.0, f32::max);
+    if total < longest * 1e-5 {
+        return None;
+    }
+
+    //Find where in the pattern the path starts.
+    let mut index = 0;
+    let mut pos = phase.rem_euclid(total);
+    if !(pos < total) {
+        pos = 0.0;
+    }
+    while pos > 0.0 && pos >= pattern[index] {
+        pos -= pattern[index];
+        index = (index + 1) % pattern.len();
+    }
+    let mut remaining = pattern[index] - pos;
+    let mut on = index % 2 == 0;
+    let starts_on = on;
+
+    let mut dashes = Vec::new();
+    let mut current = if on { vec![path[0]] } else { Vec::new() };
+    for w in path.windows(2) {
+        let (a, b) = (w[0], w[1]);
+        let l = ((b[0] - a[0]).powi(2) + (b[1] - a[1]).powi(2)).sqrt();
+        if l == 0.0 {
+            continue;
+        }
+
+        let mut t = 0.0;
+        while l - t > remaining {
+            t += remaining;
+            let k = t / l;
+            let p = [a[0] + (b[0] - a[0]) * k, a[1] + (b[1] - a[1]) * k];
+            current.push(p);
+            if on {
+                dashes.push(core::mem::replace(&mut current, Vec::new()));
+            }
+            on = !on;
+            index = (index + 1) % pattern.len();
+            remaining = pattern[index];
+        }
+        remaining -= l - t;
+        if on {
+            current.push(b);
+        }
+    }
+    if closed && on && starts_on {
+        if dashes.is_empty() {
+            return None;
+        }
+        //The last point is the first point again, so skip it when carrying on into the first dash.
+        current.extend_from_slice(&dashes[0][1..]);
+        dashes[0] = current;
+    } else if on && !current.is_empty() {
+        dashes.push(current);
+    }
+    Some(dashes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arc_segments_stay_within_tolerance() {
+        for &radius in [1.0f32, 10.0, 1000.0, 100_000.0].iter() {
+            for &tolerance in [0.25f32, 0.1].iter() {
+                let angle = core::f32::consts::PI * 2.0;
+                let n = arc_segments_within(radius, angle, tolerance);
+                //How far the middle of each piece is from the arc.
+                let error = 2.0 * radius * (angle / n as f32 * 0.25).sin().powi(2);
+                assert!(error <= tolerance * 1.01, "{} {} {}", radius, tolerance, n);
+            }
+        }
+
+        //Tolerances that a f32 can not honor still give a bounded number of pieces.
+        assert!(arc_segments_within(1000.0, 7.0, 0.0) < 5000);
+        assert!(arc_segments_within(1000.0, 7.0, -1.0) < 5000);
+        assert_eq!(arc_segments_within(0.1, 7.0, 0.25), 1);
+    }
+
+    #[test]
+    fn dash_patterns() {
+        let path = [[0.0, 0.0], [10.0, 0.0]];
+        let dashes = dash(&path, false, &[2.0, 3.0], 0.0);
+        assert_eq!(
+            dashes,
+            Some(vec![
+                vec![[0.0, 0.0], [2.0, 0.0]],
+                vec![[5.0, 0.0], [7.0, 0.0]]
+            ])
+        );
+
+        //Patterns too fine to measure out draw the solid path.
+        assert_eq!(dash(&path, false, &[1e-9, 1e-9], 0.0), None);
+        let long = [[0.0, 0.0], [1e7, 0.0]];
+        assert_eq!(dash(&long, false, &[0.01], 0.0), None);
+    }
+
+    #[test]
+    fn closed_dashes_join_at_the_start() {
+        let square = [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]];
+
+        //Starting 3 units into the pattern, the last dash runs from 39 units along the path
+        //over the start and on to the end of the first dash 1 unit in.
+        let dashes = dash(&square, true, &[4.0, 2.0], 3.0).unwrap();
+        assert_eq!(dashes.len(), 7);
+        assert_eq!(dashes[0], vec![[0.0, 1.0], [0.0, 0.0], [1.0, 0.0]]);
+        assert_eq!(dashes[1], vec![[3.0, 0.0], [7.0, 0.0]]);
+
+        //Starting in a gap leaves the pieces as they are.
+        let dashes = dash(&square, true, &[4.0, 2.0], 5.0).unwrap();
+        assert_eq!(dashes[0], vec![[1.0, 0.0], [5.0, 0.0]]);
+
+        //A dash longer than the whole path draws it solid.
+        assert_eq!(dash(&square, true, &[100.0, 1.0], 0.0), None);
+    }
+}
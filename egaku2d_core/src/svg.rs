@@ -0,0 +1,663 @@
+//! A small parser for the subset of SVG that icons and level outlines are usually made of.
+//!
+//! Path data (`M L H V C S Q T A Z` in both absolute and relative form) and the basic shapes
+//! `rect`, `circle`, `ellipse`, `line`, `polyline` and `polygon` are turned into flattened outlines.
+//! Each element may have `fill`, `fill-rule`, `stroke` and `stroke-width` attributes, either directly
+//! or in its `style` attribute. Groups, transforms, css and gradients are not supported,
+//! so those elements fall back to the svg defaults of a filled shape without a stroke.
+//! Like browsers, path data that is malformed is drawn up to the first error.
+
+use crate::curve;
+use crate::shapes::FillRule;
+use crate::stroke;
+
+type P = [f32; 2];
+
+///One of the pieces of a path that starts with a move.
+pub struct SubPath {
+    pub points: Vec<P>,
+    pub closed: bool,
+}
+
+///The outline of one element, and how it should be drawn.
+pub struct Shape {
+    pub subpaths: Vec<SubPath>,
+    ///The rule to fill the shape with, if it is filled.
+    pub fill: Option<FillRule>,
+    ///The width of the stroke, if the shape has one.
+    pub stroke: Option<f32>,
+}
+
+///Find all of the elements in the document that we know how to draw.
+pub fn parse_document(text: &str, tolerance: f32) -> Vec<Shape> {
+    let mut shapes = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        if rest.starts_with("!--") {
+            rest = match rest.find("-->") {
+                Some(end) => &rest[end + 3..],
+                None => "",
+            };
+            continue;
+        }
+        let end = match find_tag_end(rest) {
+            Some(end) => end,
+            None => break,
+        };
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+
+        if tag.starts_with('/') || tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+        let name_end = tag
+            .find(|c: char| c.is_whitespace() || c == '/')
+            .unwrap_or_else(|| tag.len());
+        let attrs = parse_attributes(&tag[name_end..]);
+        if let Some(shape) = parse_element(&tag[..name_end], &attrs, tolerance) {
+            shapes.push(shape);
+        }
+    }
+    shapes
+}
+
+///Find the closing '>' of a tag, ignoring any inside of quoted attribute values.
+fn find_tag_end(s: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '>' => return Some(i),
+            None => {}
+        }
+    }
+    None
+}
+
+fn parse_attributes(s: &str) -> Vec<(&str, &str)> {
+    let mut attrs = Vec::new();
+    let mut rest = s;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+        let eq = match rest.find('=') {
+            Some(eq) => eq,
+            None => break,
+        };
+        let name = rest[..eq].trim();
+        let after = rest[eq + 1..].trim_start();
+        let quote = match after.chars().next() {
+            Some(q) if q == '"' || q == '\'' => q,
+            _ => break,
+        };
+        let value_end = match after[1..].find(quote) {
+            Some(end) => end + 1,
+            None => break,
+        };
+        attrs.push((name, &after[1..value_end]));
+        rest = &after[value_end + 1..];
+    }
+    attrs
+}
+
+///Look up a presentation attribute, which may also be set in the style attribute.
+fn property<'a>(attrs: &[(&'a str, &'a str)], name: &str) -> Option<&'a str> {
+    let style = attrs
+        .iter()
+        .find(|a| a.0 == "style")
+        .and_then(|a| {
+            a.1.split(';').find_map(|decl| {
+                let mut kv = decl.splitn(2, ':');
+                let key = kv.next()?.trim();
+                let value = kv.next()?.trim();
+                if key == name {
+                    Some(value)
+                } else {
+                    None
+                }
+            })
+        });
+    style.or_else(|| attrs.iter().find(|a| a.0 == name).map(|a| a.1.trim()))
+}
+
+///Parse the leading number of an attribute, ignoring any unit after it.
+fn number(attrs: &[(&str, &str)], name: &str) -> Option<f32> {
+    let value = property(attrs, name)?;
+    let mut s = Scanner::new(value);
+    s.number()
+}
+
+fn parse_element(name: &str, attrs: &[(&str, &str)], tolerance: f32) -> Option<Shape> {
+    let num = |n: &str| number(attrs, n).unwrap_or(0.0);
+
+    let subpaths = match name {
+        "path" => parse_path(property(attrs, "d")?, tolerance),
+        "rect" => {
+            let (x, y, w, h) = (num("x"), num("y"), num("width"), num("height"));
+            if !(w > 0.0 && h > 0.0) {
+                return None;
+            }
+            vec![SubPath {
+                points: vec![[x, y], [x + w, y], [x + w, y + h], [x, y + h]],
+                closed: true,
+            }]
+        }
+        "circle" => {
+            let r = num("r");
+            vec![ellipse([num("cx"), num("cy")], r, r, tolerance)?]
+        }
+        "ellipse" => vec![ellipse([num("cx"), num("cy")], num("rx"), num("ry"), tolerance)?],
+        "line" => vec![SubPath {
+            points: vec![[num("x1"), num("y1")], [num("x2"), num("y2")]],
+            closed: false,
+        }],
+        "polyline" | "polygon" => {
+            let mut s = Scanner::new(property(attrs, "points")?);
+            let mut points = Vec::new();
+            while let Some(p) = s.point() {
+                points.push(p);
+            }
+            vec![SubPath {
+                points,
+                closed: name == "polygon",
+            }]
+        }
+        _ => return None,
+    };
+
+    //Lines have nothing to fill.
+    let fill = if name == "line" || property(attrs, "fill") == Some("none") {
+        None
+    } else if property(attrs, "fill-rule") == Some("evenodd") {
+        Some(FillRule::EvenOdd)
+    } else {
+        Some(FillRule::NonZero)
+    };
+    let stroke = match property(attrs, "stroke") {
+        Some(s) if s != "none" => Some(number(attrs, "stroke-width").unwrap_or(1.0)),
+        _ => None,
+    };
+    Some(Shape {
+        subpaths,
+        fill,
+        stroke,
+    })
+}
+
+fn ellipse(center: P, rx: f32, ry: f32, tolerance: f32) -> Option<SubPath> {
+    if !(rx > 0.0 && ry > 0.0) {
+        return None;
+    }
+    let n = stroke::arc_segments_within(rx.max(ry), core::f32::consts::PI * 2.0, tolerance).max(8);
+    let points = (0..n)
+        .map(|i| {
+            let t = core::f32::consts::PI * 2.0 * (i as f32 / n as f32);
+            [center[0] + rx * t.cos(), center[1] + ry * t.sin()]
+        })
+        .collect();
+    Some(SubPath {
+        points,
+        closed: true,
+    })
+}
+
+///Reads the numbers and flags out of path data and point lists.
+struct Scanner<'a> {
+    s: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn new(s: &'a str) -> Self {
+        Scanner {
+            s: s.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn skip_separators(&mut self) {
+        while let Some(&c) = self.s.get(self.pos) {
+            if !(c.is_ascii_whitespace() || c == b',') {
+                break;
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_separators();
+        self.s.get(self.pos).cloned()
+    }
+
+    fn command(&mut self) -> Option<u8> {
+        match self.peek() {
+            Some(c) if c.is_ascii_alphabetic() && c != b'e' && c != b'E' => {
+                self.pos += 1;
+                Some(c)
+            }
+            _ => None,
+        }
+    }
+
+    fn number(&mut self) -> Option<f32> {
+        self.skip_separators();
+        let start = self.pos;
+        let digits = |s: &mut Self| {
+            let begin = s.pos;
+            while s.pos < s.s.len() && s.s[s.pos].is_ascii_digit() {
+                s.pos += 1;
+            }
+            s.pos > begin
+        };
+        if self.pos < self.s.len() && (self.s[self.pos] == b'-' || self.s[self.pos] == b'+') {
+            self.pos += 1;
+        }
+        let mut any = digits(self);
+        if self.pos < self.s.len() && self.s[self.pos] == b'.' {
+            self.pos += 1;
+            any |= digits(self);
+        }
+        if !any {
+            self.pos = start;
+            return None;
+        }
+        if self.pos < self.s.len() && (self.s[self.pos] == b'e' || self.s[self.pos] == b'E') {
+            let mark = self.pos;
+            self.pos += 1;
+            if self.pos < self.s.len() && (self.s[self.pos] == b'-' || self.s[self.pos] == b'+') {
+                self.pos += 1;
+            }
+            if !digits(self) {
+                self.pos = mark;
+            }
+        }
+        core::str::from_utf8(&self.s[start..self.pos]).ok()?.parse().ok()
+    }
+
+    fn point(&mut self) -> Option<P> {
+        let x = self.number()?;
+        let y = self.number()?;
+        Some([x, y])
+    }
+
+    ///Arc flags are a single 0 or 1 and do not need to be separated from what follows.
+    fn flag(&mut self) -> Option<bool> {
+        match self.peek() {
+            Some(b'0') => {
+                self.pos += 1;
+                Some(false)
+            }
+            Some(b'1') => {
+                self.pos += 1;
+                Some(true)
+            }
+            _ => None,
+        }
+    }
+
+    fn at_number(&mut self) -> bool {
+        match self.peek() {
+            Some(c) => c.is_ascii_digit() || c == b'-' || c == b'+' || c == b'.',
+            None => false,
+        }
+    }
+}
+
+///Flatten svg path data into subpaths.
+pub fn parse_path(d: &str, tolerance: f32) -> Vec<SubPath> {
+    let mut s = Scanner::new(d);
+    let mut subpaths: Vec<SubPath> = Vec::new();
+    let mut current: Vec<P> = Vec::new();
+    let mut pos = [0.0f32; 2];
+    let mut start = [0.0f32; 2];
+    let mut command = None;
+    //The kind and last control point of the previous command, if it was a curve.
+    let mut control: Option<(u8, P)> = None;
+
+    let finish = |current: &mut Vec<P>, subpaths: &mut Vec<SubPath>, closed: bool| {
+        if current.len() > 1 {
+            subpaths.push(SubPath {
+                points: core::mem::replace(current, Vec::new()),
+                closed,
+            });
+        } else {
+            current.clear();
+        }
+    };
+
+    loop {
+        let c = match s.command() {
+            Some(c) => c,
+            //Numbers without a command repeat the last one.
+            None if command.is_some() && s.at_number() => command.unwrap(),
+            None => break,
+        };
+        let rel = c.is_ascii_lowercase();
+        let base = if rel { pos } else { [0.0, 0.0] };
+        let abs = |p: P| [p[0] + base[0], p[1] + base[1]];
+        let last = control.take();
+
+        //Only fails on malformed data, in which case we stop like browsers do.
+        let ok = (|| -> Option<()> {
+            match c.to_ascii_uppercase() {
+                b'M' => {
+                    finish(&mut current, &mut subpaths, false);
+                    pos = abs(s.point()?);
+                    start = pos;
+                    current.push(pos);
+                    //Extra coordinates after a move are lines.
+                    command = Some(if rel { b'l' } else { b'L' });
+                    return Some(());
+                }
+                b'L' => {
+                    pos = abs(s.point()?);
+                    current.push(pos);
+                }
+                b'H' => {
+                    pos = [s.number()? + base[0], pos[1]];
+                    current.push(pos);
+                }
+                b'V' => {
+                    pos = [pos[0], s.number()? + base[1]];
+                    current.push(pos);
+                }
+                b'C' => {
+                    let c0 = abs(s.point()?);
+                    let c1 = abs(s.point()?);
+                    let p1 = abs(s.point()?);
+                    if current.is_empty() {
+                        current.push(pos);
+                    }
+                    curve::flatten_cubic(pos, c0, c1, p1, tolerance, &mut current);
+                    control = Some((b'C', c1));
+                    pos = p1;
+                }
+                b'S' => {
+                    let c0 = reflect(last, b'C', pos);
+                    let c1 = abs(s.point()?);
+                    let p1 = abs(s.point()?);
+                    if current.is_empty() {
+                        current.push(pos);
+                    }
+                    curve::flatten_cubic(pos, c0, c1, p1, tolerance, &mut current);
+                    control = Some((b'C', c1));
+                    pos = p1;
+                }
+                b'Q' => {
+                    let c0 = abs(s.point()?);
+                    let p1 = abs(s.point()?);
+                    if current.is_empty() {
+                        current.push(pos);
+                    }
+                    curve::flatten_quad(pos, c0, p1, tolerance, &mut current);
+                    control = Some((b'Q', c0));
+                    pos = p1;
+                }
+                b'T' => {
+                    let c0 = reflect(last, b'Q', pos);
+                    let p1 = abs(s.point()?);
+                    if current.is_empty() {
+                        current.push(pos);
+                    }
+                    curve::flatten_quad(pos, c0, p1, tolerance, &mut current);
+                    control = Some((b'Q', c0));
+                    pos = p1;
+                }
+                b'A' => {
+                    let rx = s.number()?;
+                    let ry = s.number()?;
+                    let angle = s.number()?;
+                    let large = s.flag()?;
+                    let sweep = s.flag()?;
+                    let p1 = abs(s.point()?);
+                    if current.is_empty() {
+                        current.push(pos);
+                    }
+                    arc(pos, [rx, ry], angle, (large, sweep), p1, tolerance, &mut current);
+                    pos = p1;
+                }
+                b'Z' => {
+                    finish(&mut current, &mut subpaths, true);
+                    pos = start;
+                    command = None;
+                    return Some(());
+                }
+                _ => return None,
+            }
+            command = Some(c);
+            Some(())
+        })();
+        if ok.is_none() {
+            break;
+        }
+        //A drawing command straight after a close starts a new subpath at the start point.
+        if current.len() == 1 && current[0] != start {
+            current.insert(0, start);
+        }
+    }
+    finish(&mut current, &mut subpaths, false);
+    subpaths
+}
+
+///The first control point of a smooth curve.
+///It is the last control point of the previous curve mirrored through the current point,
+///as long as that curve was of the same kind, and otherwise the current point itself.
+fn reflect(last: Option<(u8, P)>, kind: u8, pos: P) -> P {
+    match last {
+        Some((k, c)) if k == kind => [2.0 * pos[0] - c[0], 2.0 * pos[1] - c[1]],
+        _ => pos,
+    }
+}
+
+///Flatten an svg elliptical arc, pushing the points after `p0` onto `out`.
+///This converts from the endpoint parameterization as described in the svg spec.
+fn arc(p0: P, radii: P, angle: f32, flags: (bool, bool), p1: P, tolerance: f32, out: &mut Vec<P>) {
+    let (large, sweep) = flags;
+    let (mut rx, mut ry) = (radii[0].abs(), radii[1].abs());
+    if p0 == p1 {
+        return;
+    }
+    if rx == 0.0 || ry == 0.0 {
+        out.push(p1);
+        return;
+    }
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let dx = (p0[0] - p1[0]) / 2.0;
+    let dy = (p0[1] - p1[1]) / 2.0;
+    let x1 = cos * dx + sin * dy;
+    let y1 = -sin * dx + cos * dy;
+
+    //Scale up radii that are too small to reach.
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+
+    let num = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let den = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let sign = if large == sweep { -1.0 } else { 1.0 };
+    let coef = sign * (num / den).max(0.0).sqrt();
+    let cx1 = coef * rx * y1 / ry;
+    let cy1 = -coef * ry * x1 / rx;
+    let cx = cos * cx1 - sin * cy1 + (p0[0] + p1[0]) / 2.0;
+    let cy = sin * cx1 + cos * cy1 + (p0[1] + p1[1]) / 2.0;
+
+    let vector_angle = |ux: f32, uy: f32, vx: f32, vy: f32| (ux * vy - uy * vx).atan2(ux * vx + uy * vy);
+    let ux = (x1 - cx1) / rx;
+    let uy = (y1 - cy1) / ry;
+    let vx = (-x1 - cx1) / rx;
+    let vy = (-y1 - cy1) / ry;
+    let theta = vector_angle(1.0, 0.0, ux, uy);
+    let mut delta = vector_angle(ux, uy, vx, vy);
+    if !sweep && delta > 0.0 {
+        delta -= core::f32::consts::PI * 2.0;
+    } else if sweep && delta < 0.0 {
+        delta += core::f32::consts::PI * 2.0;
+    }
+
+    let n = stroke::arc_segments_within(rx.max(ry), delta, tolerance);
+    for i in 1..n {
+        let (s, c) = (theta + delta * (i as f32 / n as f32)).sin_cos();
+        out.push([
+            cos * rx * c - sin * ry * s + cx,
+            sin * rx * c + cos * ry * s + cy,
+        ]);
+    }
+    out.push(p1);
+}
+
+///Whether the point is inside of the ring, by the even-odd rule.
+pub fn contains(ring: &[P], p: P) -> bool {
+    let mut inside = false;
+    let n = ring.len();
+    for i in 0..n {
+        let a = ring[i];
+        let b = ring[(i + n - 1) % n];
+        if (a[1] > p[1]) != (b[1] > p[1]) {
+            let x = a[0] + (p[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0]);
+            if p[0] < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(d: &str) -> Vec<Vec<P>> {
+        parse_path(d, 0.01).into_iter().map(|s| s.points).collect()
+    }
+
+    fn assert_close(a: &[P], b: &[P]) {
+        assert_eq!(a.len(), b.len(), "{:?} != {:?}", a, b);
+        for (p, q) in a.iter().zip(b.iter()) {
+            assert!(
+                (p[0] - q[0]).abs() < 1e-4 && (p[1] - q[1]).abs() < 1e-4,
+                "{:?} != {:?}",
+                a,
+                b
+            );
+        }
+    }
+
+    #[test]
+    fn implicit_repeats() {
+        let p = points("M10 10 l5 0 0 5 L0 0 20 0");
+        assert_close(&p[0], &[[10., 10.], [15., 10.], [15., 15.], [0., 0.], [20., 0.]]);
+
+        //Extra pairs after a move are lines, relative if the move was.
+        let p = points("m1 1 2 2 1 0M5 5 6 6");
+        assert_close(&p[0], &[[1., 1.], [3., 3.], [4., 3.]]);
+        assert_close(&p[1], &[[5., 5.], [6., 6.]]);
+
+        let p = points("M0 0 h10 v10 H0 v-5 5");
+        assert_close(&p[0], &[[0., 0.], [10., 0.], [10., 10.], [0., 10.], [0., 5.], [0., 10.]]);
+    }
+
+    #[test]
+    fn smooth_curves() {
+        let p = points("M0 0 C0 10 10 10 10 0 S20 -10 20 0");
+        assert_close(&p[0], &points("M0 0 C0 10 10 10 10 0 C10 -10 20 -10 20 0")[0]);
+        let p = points("m0 0 c0 10 10 10 10 0 s10 -10 10 0");
+        assert_close(&p[0], &points("M0 0 C0 10 10 10 10 0 C10 -10 20 -10 20 0")[0]);
+
+        let p = points("M0 0 Q5 10 10 0 T20 0 t10 0");
+        let q = points("M0 0 Q5 10 10 0 Q15 -10 20 0 Q25 10 30 0");
+        assert_close(&p[0], &q[0]);
+
+        //Without a curve of the same kind before it, the first control point is the current point.
+        let same = |a: &str, b: &str| assert_close(&points(a)[0], &points(b)[0]);
+        same("M0 0 S10 10 10 0", "M0 0 C0 0 10 10 10 0");
+        same("M0 0 Q5 5 5 0 S10 10 10 0", "M0 0 Q5 5 5 0 C5 0 10 10 10 0");
+        same("M0 0 L5 5 T10 0", "M0 0 L5 5 Q5 5 10 0");
+    }
+
+    #[test]
+    fn compact_numbers() {
+        let p = points("M1-2.5.5.5e1");
+        assert_close(&p[0], &[[1., -2.5], [0.5, 5.]]);
+
+        let p = points("M1e1,2E-1L-1.5e+1 0");
+        assert_close(&p[0], &[[10., 0.2], [-15., 0.]]);
+    }
+
+    #[test]
+    fn arc_flags() {
+        let min_y = |d: &str| points(d)[0].iter().fold(0.0f32, |acc, p| acc.min(p[1]));
+        let max_y = |d: &str| points(d)[0].iter().fold(0.0f32, |acc, p| acc.max(p[1]));
+
+        //y grows downwards, so a positive sweep from left to right goes over the top.
+        assert!((min_y("M0 0 A5 5 0 0 1 10 0") + 5.0).abs() < 0.01);
+        assert!(max_y("M0 0 A5 5 0 0 1 10 0") < 0.01);
+        assert!((max_y("M0 0 A5 5 0 0 0 10 0") - 5.0).abs() < 0.01);
+        assert!(min_y("M0 0 A5 5 0 0 0 10 0") > -0.01);
+
+        //The large arc goes the long way around a circle bigger than the chord.
+        assert!(min_y("M0 0 A10 10 0 0 1 10 0") > -1.5);
+        assert!(min_y("M0 0 A10 10 0 1 1 10 0") < -18.5);
+        assert!(max_y("M0 0 A10 10 0 1 0 10 0") > 18.5);
+
+        //Flags may be written without separators.
+        let p = points("M0 0a5 5 0 0110 0");
+        assert_close(&p[0][p[0].len() - 1..], &[[10., 0.]]);
+    }
+
+    #[test]
+    fn draw_after_close() {
+        let p = parse_path("M0 0 L10 0 10 10 Z l5 5 L0 10", 0.01);
+        assert_eq!(p.len(), 2);
+        assert!(p[0].closed);
+        assert!(!p[1].closed);
+        assert_close(&p[0].points, &[[0., 0.], [10., 0.], [10., 10.]]);
+        assert_close(&p[1].points, &[[0., 0.], [5., 5.], [0., 10.]]);
+    }
+
+    #[test]
+    fn style_overrides_attributes() {
+        let doc = r#"<svg><!-- a <comment> -->
+            <path d="M0 0 L1 0 1 1z" fill="red" style="stroke:#000; fill: none; stroke-width:2px"/>
+            <rect x="0" y="0" width="2" height="3" stroke="none"/>
+        </svg>"#;
+        let shapes = parse_document(doc, 0.01);
+        assert_eq!(shapes.len(), 2);
+        assert_eq!(shapes[0].fill, None);
+        assert_eq!(shapes[0].stroke, Some(2.0));
+        assert_eq!(shapes[1].fill, Some(FillRule::NonZero));
+        assert_eq!(shapes[1].stroke, None);
+        assert_close(&shapes[1].subpaths[0].points, &[[0., 0.], [2., 0.], [2., 3.], [0., 3.]]);
+    }
+
+    #[test]
+    fn fill_rules() {
+        let doc = r#"<path d="M0 0h1v1z"/>
+            <path d="M0 0h1v1z" fill-rule="evenodd"/>
+            <path d="M0 0h1v1z" fill-rule="evenodd" style="fill-rule:nonzero"/>
+            <polygon points="0 0 1 0 1 1" style="fill-rule: evenodd"/>
+            <line x1="0" y1="0" x2="1" y2="1" stroke="black"/>"#;
+        let rules: Vec<_> = parse_document(doc, 0.01).iter().map(|s| s.fill).collect();
+        assert_eq!(
+            rules,
+            vec![
+                Some(FillRule::NonZero),
+                Some(FillRule::EvenOdd),
+                Some(FillRule::NonZero),
+                Some(FillRule::EvenOdd),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn containment() {
+        let square = [[0., 0.], [10., 0.], [10., 10.], [0., 10.]];
+        assert!(contains(&square, [5., 5.]));
+        assert!(!contains(&square, [15., 5.]));
+        assert!(!contains(&square, [5., -1.]));
+    }
+}
@@ -9,15 +9,43 @@ use std::str;
 pub static VS_SRC: &'static str = "
 #version 300 es
 in vec2 position;
+in vec4 edge;
+in float size;
+in vec2 uv;
 out float ps;
+out vec2 quad_coord;
+out vec4 edge_dist;
+out vec2 world_pos;
+out float point_extent;
+out vec2 local_coord;
 
 uniform vec2 offset;
 uniform mat3 mmatrix;
 uniform float point_size;
+uniform bool quad;
+uniform float point_mul;
+
+//The corners of the two triangles drawn in place of a point that is too big.
+const vec2 CORNERS[6]=vec2[6](
+    vec2(0.0,0.0),vec2(1.0,0.0),vec2(0.0,1.0),
+    vec2(0.0,1.0),vec2(1.0,0.0),vec2(1.0,1.0)
+);
 void main() {
-    gl_PointSize = point_size;
-    vec3 pp=vec3(position+offset,1.0);
+    gl_PointSize = point_size*size;
+    vec2 p=position+offset;
+    quad_coord=vec2(0.0,0.0);
+    if(quad){
+        //Each instance is a quad the size of the point it replaces.
+        quad_coord=CORNERS[gl_VertexID];
+        p+=(quad_coord-vec2(0.5,0.5))*gl_PointSize/point_mul;
+    }
+    vec3 pp=vec3(p,1.0);
     ps=gl_PointSize;
+    edge_dist=edge;
+    world_pos=position;
+    local_coord=uv;
+    //How big a point is in the same coordinates as the vertices. 0 when not drawing points.
+    point_extent=gl_PointSize/point_mul;
     gl_Position = vec4(mmatrix*pp.xyz, 1.0);
 }";
 
@@ -26,44 +54,104 @@ pub static CIRCLE_FS_SRC: &'static str = "
 #version 300 es
 precision mediump float;
 uniform vec4 bcol;
+uniform bool antialias;
+uniform float inner;
+uniform vec2 axis_scale;
 out vec4 out_color;
 in float ps;
+in vec2 quad_coord;
+uniform bool quad;
 uniform sampler2D tex0;
 uniform vec2 texture_dim;
-uniform float texture_scale;
-uniform vec2 texture_offset;
+//The rows of the 2x3 matrix that takes a position to texture pixels.
+uniform highp vec3 texture_u;
+uniform highp vec3 texture_v;
+uniform int texture_space;
+in highp vec2 world_pos;
+in highp float point_extent;
 void main() {
+    vec2 point_coord = quad ? quad_coord : gl_PointCoord;
+
+    vec2 coord = (point_coord - vec2(0.5,0.5))/axis_scale;
+    float dis=length(coord)*2.0;
 
-    vec2 coord = gl_PointCoord - vec2(0.5,0.5);
-    float dis=dot(coord,coord);
-    if(dis > 0.25){                  //outside of circle radius?
+    float coverage=1.0;
+    if(antialias){
+        float rad=ps*0.5;
+        coverage=clamp((1.0-dis)*rad+0.5,0.0,1.0);
+        if(inner > 0.0){
+            coverage*=clamp((dis-inner)*rad+0.5,0.0,1.0);
+        }
+        if(coverage <= 0.0){
+            discard;
+        }
+    }else if(dis > 1.0 || dis < inner){     //outside of circle radius or inside the hole of a ring?
         discard;
     }
 
-    vec2 pos;
-    pos.x=gl_FragCoord.x;
-    pos.y=-gl_FragCoord.y;
-    
-    out_color = texture(tex0,( ((pos-texture_offset)/texture_dim)/texture_scale))*bcol;
+    highp vec2 pos;
+    if(texture_space==1){
+        pos=world_pos+(point_coord-vec2(0.5,0.5))*point_extent;
+    }else if(texture_space==2){
+        pos=point_coord*texture_dim;
+    }else{
+        pos.x=gl_FragCoord.x;
+        pos.y=-gl_FragCoord.y;
+    }
+
+    highp vec3 p=vec3(pos,1.0);
+    out_color = texture(tex0,vec2(dot(texture_u,p),dot(texture_v,p))/texture_dim)*bcol;
+    out_color.a*=coverage;
 }";
 
 pub static REGULAR_FS_SRC: &'static str = "
 #version 300 es
 precision mediump float;
 uniform vec4 bcol;
+uniform bool antialias;
+in vec4 edge_dist;
 out vec4 out_color;
 
 uniform vec2 texture_dim;
-uniform float texture_scale;
-uniform vec2 texture_offset;
+//The rows of the 2x3 matrix that takes a position to texture pixels.
+uniform highp vec3 texture_u;
+uniform highp vec3 texture_v;
 uniform sampler2D tex0;
+uniform int texture_space;
+uniform bool quad;
+in vec2 quad_coord;
+in highp vec2 world_pos;
+in highp float point_extent;
+in highp vec2 local_coord;
 
 void main() {
-    vec2 pos;
-    pos.x=gl_FragCoord.x;
-    pos.y=-gl_FragCoord.y;
-    out_color = texture(tex0, ((pos-texture_offset)/texture_dim)/texture_scale)*bcol;
+    //Squares are drawn as points, everything else is made of triangles.
+    bool points=point_extent>0.0;
+    vec2 point_coord = quad ? quad_coord : gl_PointCoord;
+
+    highp vec2 pos;
+    if(texture_space==1){
+        pos=world_pos;
+        if(points){
+            pos+=(point_coord-vec2(0.5,0.5))*point_extent;
+        }
+    }else if(texture_space==2){
+        //Rects know how far along they are from their left and top edges.
+        pos=(points ? point_coord : edge_dist.xz)*texture_dim;
+    }else if(texture_space==3){
+        //Other shapes carry their position within themselves in each vertex.
+        pos=local_coord*texture_dim;
+    }else{
+        pos.x=gl_FragCoord.x;
+        pos.y=-gl_FragCoord.y;
+    }
+    highp vec3 p=vec3(pos,1.0);
+    out_color = texture(tex0,vec2(dot(texture_u,p),dot(texture_v,p))/texture_dim)*bcol;
 
+    if(antialias){
+        vec4 d=edge_dist/max(fwidth(edge_dist),vec4(0.0001));
+        out_color.a*=clamp(min(min(d.x,d.y),min(d.z,d.w)),0.0,1.0);
+    }
 }";
 
 #[repr(transparent)]
@@ -76,11 +164,20 @@ pub struct TexturedShapeProgram {
     pub matrix_uniform: GLint,
     pub offset_uniform: GLint,
     pub texture_dim_uniform: GLint,
-    pub texture_offset_uniform: GLint,
-    pub texture_scale_uniform: GLint,
+    pub texture_u_uniform: GLint,
+    pub texture_v_uniform: GLint,
+    pub texture_space_uniform: GLint,
     pub point_size_uniform: GLint,
     pub bcol_uniform: GLint,
+    pub antialias_uniform: GLint,
+    pub inner_uniform: GLint,
+    pub axis_scale_uniform: GLint,
+    pub quad_uniform: GLint,
+    pub point_mul_uniform: GLint,
     pub pos_attr: GLint,
+    pub edge_attr: GLint,
+    pub size_attr: GLint,
+    pub uv_attr: GLint,
     pub sample_location: GLint,
 }
 
@@ -103,6 +200,8 @@ impl TexturedShapeProgram {
 
         let matrix = [[scalex, 0.0, 0.0], [0.0, -scaley, 0.0], [tx, ty, 1.0]];
 
+        let point_mul = window_dim.width as f32 / game_width;
+
         unsafe {
             gl::UseProgram(self.program);
             gl_ok!();
@@ -113,9 +212,11 @@ impl TexturedShapeProgram {
                 std::mem::transmute(&matrix[0][0]),
             );
             gl_ok!();
+            gl::Uniform1f(self.point_mul_uniform, point_mul);
+            gl_ok!();
         }
 
-        PointMul(window_dim.width as f32 / game_width)
+        PointMul(point_mul)
     }
 
     pub(crate) fn set_buffer_and_draw(
@@ -129,7 +230,7 @@ impl TexturedShapeProgram {
         let col = common.color;
         let buffer_id = buffer_info.id;
         let offset = common.offset;
-        let length = buffer_info.length;
+        let stride = un.stride;
 
         unsafe {
             gl::UseProgram(self.program);
@@ -144,6 +245,18 @@ impl TexturedShapeProgram {
             gl::Uniform4fv(self.bcol_uniform, 1, col.as_ptr() as *const _);
             gl_ok!();
 
+            gl::Uniform1i(self.antialias_uniform, un.antialias as i32);
+            gl_ok!();
+
+            gl::Uniform1f(self.inner_uniform, un.inner);
+            gl_ok!();
+
+            gl::Uniform2f(self.axis_scale_uniform, un.axis_scale[0], un.axis_scale[1]);
+            gl_ok!();
+
+            gl::Uniform1i(self.quad_uniform, un.quad as i32);
+            gl_ok!();
+
             gl::BindBuffer(gl::ARRAY_BUFFER, buffer_id);
             gl_ok!();
 
@@ -164,10 +277,26 @@ impl TexturedShapeProgram {
                     gl::Uniform2f(self.texture_dim_uniform, t.dim[0] as f32, t.dim[1] as f32);
                     gl_ok!();
 
-                    gl::Uniform2f(self.texture_offset_uniform, offset[0], offset[1]);
+                    //Without a full transform, scale about the offset.
+                    let [u, v] = un.texture_transform.unwrap_or([
+                        [1.0 / scale, 0.0, -offset[0] / scale],
+                        [0.0, 1.0 / scale, -offset[1] / scale],
+                    ]);
+                    gl::Uniform3f(self.texture_u_uniform, u[0], u[1], u[2]);
                     gl_ok!();
 
-                    gl::Uniform1f(self.texture_scale_uniform, scale);
+                    gl::Uniform3f(self.texture_v_uniform, v[0], v[1], v[2]);
+                    gl_ok!();
+
+                    let space = match un.texture_space {
+                        sprite::TextureSpace::Screen => 0,
+                        sprite::TextureSpace::World => 1,
+                        sprite::TextureSpace::Local if mode == gl::POINTS || un.local_edges => 2,
+                        sprite::TextureSpace::Local if un.uvs => 3,
+                        //Shapes with no idea of where they are within themselves stay in world space.
+                        sprite::TextureSpace::Local => 1,
+                    };
+                    gl::Uniform1i(self.texture_space_uniform, space);
                     gl_ok!();
                 }
                 None => {
@@ -183,18 +312,42 @@ impl TexturedShapeProgram {
                 2,
                 gl::FLOAT,
                 gl::FALSE as GLboolean,
-                0 as i32,
+                stride as i32,
                 core::ptr::null(),
             );
             gl_ok!();
 
-            gl::DrawArrays(mode, 0 as i32, length as i32);
-
-            gl_ok!();
+            circle_program::set_edge_attr(self.edge_attr, un);
+            circle_program::set_size_attr(self.size_attr, un);
+            circle_program::set_uv_attr(self.uv_attr, un);
+
+            if un.quad {
+                let attrs = [self.pos_attr, self.edge_attr, self.size_attr];
+                vbo::set_divisors(&attrs, 1);
+                vbo::draw_point_quads(buffer_info);
+                vbo::set_divisors(&attrs, 0);
+            } else {
+                vbo::draw(mode, buffer_info);
+            }
 
             gl::DisableVertexAttribArray(self.pos_attr as GLuint);
             gl_ok!();
 
+            if un.edges && self.edge_attr >= 0 {
+                gl::DisableVertexAttribArray(self.edge_attr as GLuint);
+                gl_ok!();
+            }
+
+            if un.sizes && self.size_attr >= 0 {
+                gl::DisableVertexAttribArray(self.size_attr as GLuint);
+                gl_ok!();
+            }
+
+            if un.uvs && self.uv_attr >= 0 {
+                gl::DisableVertexAttribArray(self.uv_attr as GLuint);
+                gl_ok!();
+            }
+
             gl::BindBuffer(gl::ARRAY_BUFFER, 0);
             gl_ok!();
         }
@@ -221,8 +374,13 @@ impl TexturedShapeProgram {
             gl::UseProgram(program);
             gl_ok!();
 
-            let temp=CString::new("texture_scale").unwrap();
-            let texture_scale_uniform: GLint =
+            let temp=CString::new("texture_u").unwrap();
+            let texture_u_uniform: GLint =
+                gl::GetUniformLocation(program, temp.as_ptr());
+            gl_ok!();
+
+            let temp=CString::new("texture_space").unwrap();
+            let texture_space_uniform: GLint =
                 gl::GetUniformLocation(program, temp.as_ptr());
             gl_ok!();
 
@@ -231,8 +389,8 @@ impl TexturedShapeProgram {
                 gl::GetUniformLocation(program, temp.as_ptr());
             gl_ok!();
 
-            let temp=CString::new("texture_offset").unwrap();
-            let texture_offset_uniform: GLint =
+            let temp=CString::new("texture_v").unwrap();
+            let texture_v_uniform: GLint =
                 gl::GetUniformLocation(program, temp.as_ptr());
             gl_ok!();
 
@@ -256,11 +414,53 @@ impl TexturedShapeProgram {
                 gl::GetUniformLocation(program, temp.as_ptr());
             gl_ok!();
 
+            let temp=CString::new("antialias").unwrap();
+            let antialias_uniform: GLint =
+                gl::GetUniformLocation(program, temp.as_ptr());
+            gl_ok!();
+
+            let temp=CString::new("inner").unwrap();
+            let inner_uniform: GLint =
+                gl::GetUniformLocation(program, temp.as_ptr());
+            gl_ok!();
+
+            let temp=CString::new("axis_scale").unwrap();
+            let axis_scale_uniform: GLint =
+                gl::GetUniformLocation(program, temp.as_ptr());
+            gl_ok!();
+
+            let temp=CString::new("quad").unwrap();
+            let quad_uniform: GLint =
+                gl::GetUniformLocation(program, temp.as_ptr());
+            gl_ok!();
+
+            let temp=CString::new("point_mul").unwrap();
+            let point_mul_uniform: GLint =
+                gl::GetUniformLocation(program, temp.as_ptr());
+            gl_ok!();
+
             let temp=CString::new("position").unwrap();
             let pos_attr =
                 gl::GetAttribLocation(program, temp.as_ptr());
             gl_ok!();
 
+            //This may be -1 if the fragment shader does not use the edge distances.
+            let temp=CString::new("edge").unwrap();
+            let edge_attr =
+                gl::GetAttribLocation(program, temp.as_ptr());
+            gl_ok!();
+
+            let temp=CString::new("size").unwrap();
+            let size_attr =
+                gl::GetAttribLocation(program, temp.as_ptr());
+            gl_ok!();
+
+            //This may be -1 if the fragment shader does not use the uvs.
+            let temp=CString::new("uv").unwrap();
+            let uv_attr =
+                gl::GetAttribLocation(program, temp.as_ptr());
+            gl_ok!();
+
             let temp=CString::new("tex0").unwrap();
             let sample_location =
                 gl::GetAttribLocation(program, temp.as_ptr());
@@ -270,12 +470,21 @@ impl TexturedShapeProgram {
                 program,
                 offset_uniform,
                 texture_dim_uniform,
-                texture_offset_uniform,
-                texture_scale_uniform,
+                texture_u_uniform,
+                texture_v_uniform,
+                texture_space_uniform,
                 point_size_uniform,
                 matrix_uniform,
                 bcol_uniform,
+                antialias_uniform,
+                inner_uniform,
+                axis_scale_uniform,
+                quad_uniform,
+                point_mul_uniform,
                 pos_attr,
+                edge_attr,
+                size_attr,
+                uv_attr,
                 sample_location,
             }
         }
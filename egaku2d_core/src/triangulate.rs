@@ -0,0 +1,364 @@
+//! Ear clipping triangulation of simple polygons with holes.
+//!
+//! Holes are first stitched into the outline with a zero width bridge,
+//! turning the polygon into one weakly simple ring which is then clipped ear by ear.
+//! This is O(n^2) in the number of points, which is fine for the level geometry
+//! and hull sized polygons this is meant for.
+
+use super::*;
+
+type P = [f32; 2];
+
+#[inline(always)]
+fn cross(o: P, a: P, b: P) -> f32 {
+    (a[0] - o[0]) * (b[1] - o[1]) - (a[1] - o[1]) * (b[0] - o[0])
+}
+
+///Twice the signed area of the ring.
+pub fn signed_area(ring: &[P]) -> f32 {
+    let mut total = 0.0;
+    let mut prev = match ring.last() {
+        Some(&p) => p,
+        None => return 0.0,
+    };
+    for &p in ring.iter() {
+        total += prev[0] * p[1] - p[0] * prev[1];
+        prev = p;
+    }
+    total
+}
+
+///Copy a ring, dropping repeated points and the closing point if the user repeated the first one.
+///The ring is made to wind positive if `positive` is set, and negative otherwise.
+fn clean_ring(ring: &[P], positive: bool) -> Vec<P> {
+    let mut out: Vec<P> = Vec::with_capacity(ring.len());
+    for &p in ring.iter() {
+        if out.last() != Some(&p) {
+            out.push(p);
+        }
+    }
+    while out.len() > 1 && out.first() == out.last() {
+        out.pop();
+    }
+    if (signed_area(&out) > 0.0) != positive {
+        out.reverse();
+    }
+    out
+}
+
+#[inline(always)]
+fn in_triangle(a: P, b: P, c: P, p: P) -> bool {
+    cross(a, b, p) >= 0.0 && cross(b, c, p) >= 0.0 && cross(c, a, p) >= 0.0
+}
+
+///Whether `m` lies within the corner of the positively wound ring at `i`.
+fn in_corner(ring: &[P], i: usize, m: P) -> bool {
+    let n = ring.len();
+    let (a, p, b) = (ring[(i + n - 1) % n], ring[i], ring[(i + 1) % n]);
+    let (after_a, before_b) = (cross(a, p, m) > 0.0, cross(p, b, m) > 0.0);
+    if cross(a, p, b) >= 0.0 {
+        after_a && before_b
+    } else {
+        after_a || before_b
+    }
+}
+
+///Splice a negatively wound hole into a positively wound outline.
+///Returns false if no bridge could be found, in which case the outline is left untouched.
+fn bridge_hole(outline: &mut Vec<P>, hole: &[P]) -> bool {
+    let (mi, m) = hole
+        .iter()
+        .cloned()
+        .enumerate()
+        .fold((0, hole[0]), |acc, (i, p)| if p[0] > acc.1[0] { (i, p) } else { acc });
+
+    //Cast a ray from m towards +x and find the closest edge it hits.
+    let n = outline.len();
+    let mut best: Option<(f32, usize)> = None;
+    for i in 0..n {
+        let a = outline[i];
+        let b = outline[(i + 1) % n];
+        let straddles = (a[1] <= m[1] && b[1] >= m[1]) || (a[1] >= m[1] && b[1] <= m[1]);
+        if !straddles || a[1] == b[1] {
+            continue;
+        }
+        let t = (m[1] - a[1]) / (b[1] - a[1]);
+        let x = a[0] + t * (b[0] - a[0]);
+        if x < m[0] {
+            continue;
+        }
+        if best.map(|(bx, _)| x < bx).unwrap_or(true) {
+            //The vertex of the hit edge with the larger x is a candidate to bridge to.
+            let pi = if a[0] > b[0] { i } else { (i + 1) % n };
+            best = Some((x, pi));
+        }
+    }
+
+    let (ix, mut pi) = match best {
+        Some(b) => b,
+        None => return false,
+    };
+
+    //A reflex vertex inside the triangle (m,i,p) would block the bridge.
+    //If there are any, pick the one with the smallest angle to the ray.
+    let ipoint = [ix, m[1]];
+    let p = outline[pi];
+    if p != ipoint {
+        let mut best_tan = core::f32::INFINITY;
+        for i in 0..n {
+            let r = outline[i];
+            let prev = outline[(i + n - 1) % n];
+            let next = outline[(i + 1) % n];
+            if i == pi || cross(prev, r, next) > 0.0 || r[0] < m[0] {
+                continue;
+            }
+            let inside = if p[1] > m[1] {
+                in_triangle(m, ipoint, p, r)
+            } else {
+                in_triangle(m, p, ipoint, r)
+            };
+            if inside {
+                let tan = (r[1] - m[1]).abs() / (r[0] - m[0]).max(core::f32::EPSILON);
+                if tan < best_tan {
+                    best_tan = tan;
+                    pi = i;
+                }
+            }
+        }
+    }
+
+    //Earlier bridges leave two copies of the points they join, and only one of them faces the hole.
+    //Bridging from the other one would cross the ring.
+    let p = outline[pi];
+    if !in_corner(outline, pi, m) {
+        if let Some(i) = (0..n).find(|&i| outline[i] == p && in_corner(outline, i, m)) {
+            pi = i;
+        }
+    }
+
+    let mut spliced = Vec::with_capacity(outline.len() + hole.len() + 2);
+    spliced.extend_from_slice(&outline[..=pi]);
+    spliced.extend_from_slice(&hole[mi..]);
+    spliced.extend_from_slice(&hole[..=mi]);
+    spliced.extend_from_slice(&outline[pi..]);
+    *outline = spliced;
+    true
+}
+
+fn is_ear(ring: &[P], idx: &[usize], i: usize) -> bool {
+    let n = idx.len();
+    let a = ring[idx[(i + n - 1) % n]];
+    let b = ring[idx[i]];
+    let c = ring[idx[(i + 1) % n]];
+
+    if cross(a, b, c) <= 0.0 {
+        return false;
+    }
+
+    idx.iter().map(|&j| ring[j]).all(|p| {
+        //Bridges duplicate points, so points that coincide with a corner do not count.
+        p == a || p == b || p == c || !in_triangle(a, b, c, p)
+    })
+}
+
+///Triangulate a simple polygon with optional holes, pushing the triangles onto `out`.
+///The outline and the holes may be wound in either direction.
+pub fn triangulate(outline: &[P], holes: &[&[P]], out: &mut Vec<circle_program::Vertex>) {
+    let mut ring = clean_ring(outline, true);
+    if ring.len() < 3 {
+        return;
+    }
+
+    let mut holes: Vec<Vec<P>> = holes
+        .iter()
+        .map(|h| clean_ring(h, false))
+        .filter(|h| h.len() >= 3)
+        .collect();
+
+    //Bridge the holes from right to left so that earlier bridges never cross later ones.
+    let max_x = |h: &Vec<P>| h.iter().fold(core::f32::NEG_INFINITY, |acc, p| acc.max(p[0]));
+    holes.sort_by(|a, b| {
+        max_x(b)
+            .partial_cmp(&max_x(a))
+            .unwrap_or(core::cmp::Ordering::Equal)
+    });
+    for h in holes.iter() {
+        bridge_hole(&mut ring, h);
+    }
+
+    let mut idx: Vec<usize> = (0..ring.len()).collect();
+    let mut i = 0;
+    let mut misses = 0;
+    while idx.len() > 3 {
+        let n = idx.len();
+        i %= n;
+
+        let ear = is_ear(&ring, &idx, i);
+
+        //If we went all the way around without finding an ear the remaining ring is degenerate.
+        //Clip anyway so that we always terminate.
+        if ear || misses >= n {
+            let a = ring[idx[(i + n - 1) % n]];
+            let b = ring[idx[i]];
+            let c = ring[idx[(i + 1) % n]];
+            if cross(a, b, c) > 0.0 {
+                out.push(circle_program::Vertex(a));
+                out.push(circle_program::Vertex(b));
+                out.push(circle_program::Vertex(c));
+            }
+            idx.remove(i);
+            misses = 0;
+        } else {
+            i += 1;
+            misses += 1;
+        }
+    }
+
+    let (a, b, c) = (ring[idx[0]], ring[idx[1]], ring[idx[2]]);
+    if cross(a, b, c) > 0.0 {
+        out.push(circle_program::Vertex(a));
+        out.push(circle_program::Vertex(b));
+        out.push(circle_program::Vertex(c));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ring_area(ring: &[P]) -> f32 {
+        signed_area(ring).abs() * 0.5
+    }
+
+    ///Even-odd test against the outline and every hole.
+    fn inside(p: P, rings: &[&[P]]) -> bool {
+        let mut crossings = 0;
+        for ring in rings.iter() {
+            for i in 0..ring.len() {
+                let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+                if (a[1] > p[1]) != (b[1] > p[1]) {
+                    let x = a[0] + (p[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0]);
+                    if x > p[0] {
+                        crossings += 1;
+                    }
+                }
+            }
+        }
+        crossings % 2 == 1
+    }
+
+    ///The triangles must add up to the area of the polygon, and each one must lie inside of it,
+    ///which together means they cover it without overlapping.
+    fn check(outline: &[P], holes: &[&[P]]) {
+        let expected = ring_area(outline) - holes.iter().map(|h| ring_area(h)).sum::<f32>();
+        for &reverse in [false, true].iter() {
+            let mut outline = outline.to_vec();
+            if reverse {
+                outline.reverse();
+            }
+            let mut out = Vec::new();
+            triangulate(&outline, holes, &mut out);
+            assert_eq!(out.len() % 3, 0);
+
+            let mut rings = vec![&outline[..]];
+            rings.extend_from_slice(holes);
+            let mut total = 0.0;
+            for t in out.chunks(3) {
+                let (a, b, c) = (t[0].0, t[1].0, t[2].0);
+                total += cross(a, b, c).abs() * 0.5;
+                let centroid = [(a[0] + b[0] + c[0]) / 3.0, (a[1] + b[1] + c[1]) / 3.0];
+                assert!(inside(centroid, &rings), "{:?} is outside", t);
+            }
+            assert!(
+                (total - expected).abs() < expected * 1e-4,
+                "triangles cover {} of {}",
+                total,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn concave() {
+        let l_shape = [
+            [0.0, 0.0],
+            [2.0, 0.0],
+            [2.0, 1.0],
+            [1.0, 1.0],
+            [1.0, 3.0],
+            [0.0, 3.0],
+        ];
+        check(&l_shape, &[]);
+
+        let mut comb = vec![[0.0, 0.0]];
+        for i in 0..8 {
+            let x = i as f32 * 2.0;
+            comb.extend_from_slice(&[[x, 5.0], [x + 1.0, 5.0], [x + 1.0, 1.0], [x + 2.0, 1.0]]);
+        }
+        comb.extend_from_slice(&[[16.0, 5.0], [17.0, 5.0], [17.0, 0.0]]);
+        check(&comb, &[]);
+    }
+
+    #[test]
+    fn collinear() {
+        //Points along the edges, and a run of points along one line.
+        let square = [
+            [0.0, 0.0],
+            [1.0, 0.0],
+            [2.0, 0.0],
+            [3.0, 0.0],
+            [3.0, 1.5],
+            [3.0, 3.0],
+            [0.0, 3.0],
+            [0.0, 2.0],
+            [0.0, 1.0],
+        ];
+        check(&square, &[]);
+
+        //A triangle with points along its long side, so most of the ears there have no area.
+        let triangle = [
+            [0.0, 0.0],
+            [4.0, 0.0],
+            [4.0, 4.0],
+            [3.0, 3.0],
+            [2.0, 2.0],
+            [1.0, 1.0],
+        ];
+        check(&triangle, &[]);
+    }
+
+    #[test]
+    fn holes() {
+        let outline = [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]];
+        let a = [[1.0, 1.0], [3.0, 1.0], [3.0, 3.0], [1.0, 3.0]];
+        let b = [[6.0, 1.0], [8.0, 1.0], [8.0, 4.0], [6.0, 4.0]];
+        let c = [[2.0, 6.0], [9.0, 6.0], [9.0, 9.0], [2.0, 9.0]];
+        let mut reversed = b;
+        reversed.reverse();
+        check(&outline, &[&a, &reversed, &c]);
+
+        //Holes stacked at the same x, so their bridges line up.
+        let d = [[4.0, 1.0], [5.0, 1.0], [5.0, 2.0], [4.0, 2.0]];
+        let e = [[4.0, 4.0], [5.0, 4.0], [5.0, 5.0], [4.0, 5.0]];
+        check(&outline, &[&d, &e]);
+    }
+
+    #[test]
+    fn hole_in_a_u() {
+        //The ray from the hole hits the inside of the other arm first.
+        let u = [
+            [0.0, 0.0],
+            [10.0, 0.0],
+            [10.0, 10.0],
+            [7.0, 10.0],
+            [7.0, 3.0],
+            [3.0, 3.0],
+            [3.0, 10.0],
+            [0.0, 10.0],
+        ];
+        let in_left_arm = [[1.0, 5.0], [2.0, 5.0], [2.0, 8.0], [1.0, 8.0]];
+        check(&u, &[&in_left_arm]);
+        let in_base = [[4.0, 1.0], [6.0, 1.0], [6.0, 2.0], [4.0, 2.0]];
+        check(&u, &[&in_left_arm, &in_base]);
+    }
+}
@@ -4,7 +4,62 @@ use core::marker::PhantomData;
 #[derive(Copy, Clone, Debug)]
 pub(crate) struct BufferInfo {
     pub id: u32,
+    ///The number of vertices to draw, or the number of indices if there is an index buffer.
     pub length: usize,
+    pub index: Option<IndexInfo>,
+}
+
+impl BufferInfo {
+    ///Draw the vertices through the given indices instead of in order.
+    pub(crate) fn with_indices(self, index: IndexInfo, length: usize) -> BufferInfo {
+        BufferInfo {
+            id: self.id,
+            length,
+            index: Some(index),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct IndexInfo {
+    pub id: u32,
+    ///Either UNSIGNED_SHORT or UNSIGNED_INT.
+    pub ty: GLenum,
+}
+
+///Draw the buffer that is currently bound, through its index buffer if it has one.
+pub(crate) unsafe fn draw(mode: GLenum, info: BufferInfo) {
+    match info.index {
+        Some(index) => {
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, index.id);
+            gl_ok!();
+
+            gl::DrawElements(mode, info.length as i32, index.ty, core::ptr::null());
+            gl_ok!();
+
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, 0);
+            gl_ok!();
+        }
+        None => {
+            gl::DrawArrays(mode, 0 as i32, info.length as i32);
+            gl_ok!();
+        }
+    }
+}
+
+///Draw a quad made of two triangles for every vertex of the buffer that is currently bound.
+///The vertex attributes must have a divisor of 1 so that each quad gets one vertex.
+pub(crate) unsafe fn draw_point_quads(info: BufferInfo) {
+    gl::DrawArraysInstanced(gl::TRIANGLES, 0, 6, info.length as i32);
+    gl_ok!();
+}
+
+///Set the divisor of each of the attributes, skipping any the program does not have.
+pub(crate) unsafe fn set_divisors(attrs: &[GLint], divisor: GLuint) {
+    for &attr in attrs.iter().filter(|&&a| a >= 0) {
+        gl::VertexAttribDivisor(attr as GLuint, divisor);
+        gl_ok!();
+    }
 }
 
 #[derive(Debug)]
@@ -42,12 +97,107 @@ impl<V: core::fmt::Debug + Copy + Clone> StaticBuffer<V> {
             info: BufferInfo {
                 id: vbo,
                 length: data.len(),
+                index: None,
             },
             _p: PhantomData,
         }
     }
 }
 
+///Indices into a static vertex buffer.
+#[derive(Debug)]
+pub struct StaticIndexBuffer {
+    info: IndexInfo,
+    length: usize,
+}
+impl Drop for StaticIndexBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.info.id);
+        }
+    }
+}
+impl StaticIndexBuffer {
+    pub(crate) fn get_info(&self) -> (IndexInfo, usize) {
+        (self.info, self.length)
+    }
+    pub(crate) fn new<I: Copy>(data: &[I], ty: GLenum) -> StaticIndexBuffer {
+        let mut id = 0;
+        unsafe {
+            gl::GenBuffers(1, &mut id);
+            gl_ok!();
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, id);
+            gl_ok!();
+            gl::BufferData(
+                gl::ELEMENT_ARRAY_BUFFER,
+                (data.len() * mem::size_of::<I>()) as GLsizeiptr,
+                data.as_ptr() as *const std::ffi::c_void,
+                gl::STATIC_DRAW,
+            );
+            gl_ok!();
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, 0);
+            gl_ok!();
+        }
+        StaticIndexBuffer {
+            info: IndexInfo { id, ty },
+            length: data.len(),
+        }
+    }
+}
+
+///An index buffer that is reused between sessions and only reallocated when it needs to grow.
+///It can hold either u16 or u32 indices.
+#[derive(Debug)]
+pub struct GrowableIndexBuffer {
+    id: u32,
+    bytes: usize,
+}
+impl Drop for GrowableIndexBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.id);
+        }
+    }
+}
+impl GrowableIndexBuffer {
+    pub(crate) fn new() -> GrowableIndexBuffer {
+        let mut id: u32 = 0;
+        unsafe {
+            gl::GenBuffers(1, &mut id);
+        }
+        GrowableIndexBuffer { id, bytes: 0 }
+    }
+
+    pub(crate) fn send_to_gpu<I: Copy>(&mut self, arr: &[I], ty: GLenum) -> IndexInfo {
+        let bytes = arr.len() * mem::size_of::<I>();
+        unsafe {
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.id);
+            gl_ok!();
+            if bytes > self.bytes {
+                gl::BufferData(
+                    gl::ELEMENT_ARRAY_BUFFER,
+                    bytes as GLsizeiptr,
+                    arr.as_ptr() as *const _,
+                    gl::DYNAMIC_DRAW,
+                );
+                gl_ok!();
+                self.bytes = bytes;
+            } else {
+                gl::BufferSubData(
+                    gl::ELEMENT_ARRAY_BUFFER,
+                    0,
+                    bytes as GLsizeiptr,
+                    arr.as_ptr() as *const _,
+                );
+                gl_ok!();
+            }
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, 0);
+            gl_ok!();
+        }
+        IndexInfo { id: self.id, ty }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct GrowableBuffer<B> {
     vbo: u32,
@@ -126,6 +276,7 @@ impl<B> GrowableBuffer<B> {
         BufferInfo {
             id: self.vbo,
             length,
+            index: None,
         }
     }
 }
@@ -0,0 +1,319 @@
+//! Contains the texture atlas code. See the crate level documentation.
+
+use egaku2d_core::gl;
+use egaku2d_core::gl_ok;
+use egaku2d_core::sprite::Texture;
+use std::collections::BTreeMap;
+
+///Where an image ended up in an atlas.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AtlasHandle {
+    ///Which of the atlas textures the image is in.
+    pub page: usize,
+    ///The tile index to pass to `SpriteSession::add` along with that texture.
+    pub index: u16,
+}
+
+///Many images packed into textures, with images of about the same size sharing pages.
+///Every image in a page can be drawn in the same draw call.
+pub struct Atlas {
+    pages: Vec<Texture>,
+    handles: Vec<AtlasHandle>,
+}
+
+impl Atlas {
+    pub fn pages(&self) -> &[Texture] {
+        &self.pages
+    }
+
+    ///The texture that an image is in.
+    pub fn page(&self, handle: AtlasHandle) -> &Texture {
+        &self.pages[handle.page]
+    }
+
+    ///Where the image that was added to the builder at this position ended up.
+    pub fn handle(&self, image: usize) -> AtlasHandle {
+        self.handles[image]
+    }
+
+    pub fn handles(&self) -> &[AtlasHandle] {
+        &self.handles
+    }
+}
+
+///Collects loose images to be packed into an atlas.
+///Images are sorted into size classes by rounding their width and height up to powers of two,
+///and each class gets pages of its own. Within a page every image gets a tile as big as the biggest
+///image of its class, so each page can be drawn like any other tile set.
+///Smaller images are centered in their tile and the rest of it is left transparent, so they keep their size.
+///The edges of each image are extruded out by the padding, so that filtering never blends an image
+///with transparent black or with its neighbours.
+pub struct AtlasBuilder {
+    images: Vec<image::RgbaImage>,
+    padding: u32,
+    max_size: Option<u32>,
+}
+
+impl Default for AtlasBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AtlasBuilder {
+    pub fn new() -> Self {
+        AtlasBuilder {
+            images: Vec::new(),
+            padding: 1,
+            max_size: None,
+        }
+    }
+
+    ///Set how many pixels of extruded border go around each image. The default is 1.
+    ///Each mipmap level halves the padding, so pages only use the mipmap levels that
+    ///still have at least one pixel of it. Grow the padding to use more of them.
+    pub fn with_padding(&mut self, padding: u32) -> &mut Self {
+        self.padding = padding;
+        self
+    }
+
+    ///Set the largest width and height of a page.
+    ///Pages are never bigger than the driver allows either way.
+    pub fn with_max_size(&mut self, max_size: u32) -> &mut Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    ///Load an image from a file and add it.
+    ///Returns the position to look its handle up with once the atlas is built.
+    pub fn add_file(&mut self, file: &str) -> image::ImageResult<usize> {
+        let img = image::open(file)?;
+        Ok(self.add_image(img.to_rgba()))
+    }
+
+    ///Add an image that has already been loaded.
+    ///Returns the position to look its handle up with once the atlas is built.
+    pub fn add_image(&mut self, image: image::RgbaImage) -> usize {
+        self.images.push(image);
+        self.images.len() - 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.images.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.images.is_empty()
+    }
+}
+
+///The images in one page, all with tiles of the same size.
+struct PageLayout {
+    ///The size of the biggest image in the page, which every tile is made big enough for.
+    content: [u32; 2],
+    cols: u32,
+    rows: u32,
+    ///The positions of the images in the builder, in tile order.
+    images: Vec<usize>,
+}
+
+///Images whose width and height round up to the same powers of two are in the same class.
+///Every image in a class covers more than half of the width and height of the biggest one.
+fn size_class(image: &image::RgbaImage) -> (u32, u32) {
+    let (w, h) = image.dimensions();
+    (w.max(1).next_power_of_two(), h.max(1).next_power_of_two())
+}
+
+///Decide which page and tile every image goes in, without touching the gpu.
+///Panics if a single padded image does not fit in a page of `max_size`.
+fn layout(
+    images: &[image::RgbaImage],
+    padding: u32,
+    max_size: u32,
+) -> (Vec<PageLayout>, Vec<AtlasHandle>) {
+    let mut classes: BTreeMap<(u32, u32), Vec<usize>> = BTreeMap::new();
+    for (i, img) in images.iter().enumerate() {
+        classes.entry(size_class(img)).or_default().push(i);
+    }
+
+    let mut pages = Vec::new();
+    let mut handles = vec![AtlasHandle { page: 0, index: 0 }; images.len()];
+    for members in classes.values() {
+        let content = members.iter().fold([1, 1], |acc, &i| {
+            let (w, h) = images[i].dimensions();
+            [acc[0].max(w), acc[1].max(h)]
+        });
+        let cell = [content[0] + 2 * padding, content[1] + 2 * padding];
+
+        //A texture can have at most 255 tiles along each side.
+        let cols = (max_size / cell[0]).min(255);
+        let rows = (max_size / cell[1]).min(255);
+        assert!(
+            cols > 0 && rows > 0,
+            "The images are too big to fit in a texture"
+        );
+
+        for chunk in members.chunks((cols * rows) as usize) {
+            let n = chunk.len() as u32;
+            let page_cols = cols.min(n);
+            let page_rows = (n + page_cols - 1) / page_cols;
+
+            //Tiles are indexed left to right, then top to bottom.
+            for (index, &image) in chunk.iter().enumerate() {
+                handles[image] = AtlasHandle {
+                    page: pages.len(),
+                    index: index as u16,
+                };
+            }
+            pages.push(PageLayout {
+                content,
+                cols: page_cols,
+                rows: page_rows,
+                images: chunk.to_vec(),
+            });
+        }
+    }
+    (pages, handles)
+}
+
+///Pack the images into textures.
+///This is not a tight packing. Sprites pick their tile out of a uniform grid, so every tile of a page
+///has to be the same size. Instead images are grouped into pages by size class (see `size_class`),
+///which keeps a small image from taking up a tile as big as the biggest image in the atlas.
+///Panics if a single padded image does not fit in a page.
+pub(crate) fn build(builder: &AtlasBuilder) -> Atlas {
+    let max_size = unsafe {
+        let mut max_size = 0;
+        gl::GetIntegerv(gl::MAX_TEXTURE_SIZE, &mut max_size);
+        gl_ok!();
+        max_size.max(1) as u32
+    };
+    let max_size = builder.max_size.map_or(max_size, |m| m.min(max_size));
+    let padding = builder.padding;
+
+    let (layouts, handles) = layout(&builder.images, padding, max_size);
+
+    let mut pages = Vec::with_capacity(layouts.len());
+    for page in layouts.iter() {
+        let cell = [page.content[0] + 2 * padding, page.content[1] + 2 * padding];
+        let width = page.cols * cell[0];
+        let height = page.rows * cell[1];
+
+        let mut img = image::RgbaImage::new(width, height);
+        for (i, &image) in page.images.iter().enumerate() {
+            let i = i as u32;
+            let corner = [(i % page.cols) * cell[0], (i / page.cols) * cell[1]];
+            let tile = &builder.images[image];
+            blit_tile(&mut img, tile, corner, page.content, padding);
+        }
+
+        let id = crate::build_opengl_mipmapped_texture(width, height, img);
+        limit_mipmaps(id, padding);
+        let grid_dim = [page.cols as u8, page.rows as u8];
+        let dim = [width as f32, height as f32];
+        pages.push(unsafe { Texture::new_with_padding(id, grid_dim, dim, padding as f32) });
+    }
+
+    Atlas { pages, handles }
+}
+
+///Stop the page from using mipmap levels where the padding is less than a pixel,
+///since the tiles would bleed into each other there.
+fn limit_mipmaps(id: gl::types::GLuint, padding: u32) {
+    let max_level = if padding > 0 {
+        31 - padding.leading_zeros()
+    } else {
+        0
+    };
+    unsafe {
+        gl::BindTexture(gl::TEXTURE_2D, id);
+        gl_ok!();
+
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAX_LEVEL, max_level as i32);
+        gl_ok!();
+
+        gl::BindTexture(gl::TEXTURE_2D, 0);
+        gl_ok!();
+    }
+}
+
+///Copy the image into the middle of its tile, extruding its edges out by the padding.
+///The rest of the tile is left as it is, which is transparent in a new page.
+fn blit_tile(
+    page: &mut image::RgbaImage,
+    tile: &image::RgbaImage,
+    corner: [u32; 2],
+    content: [u32; 2],
+    padding: u32,
+) {
+    let (w, h) = tile.dimensions();
+    if w == 0 || h == 0 {
+        return;
+    }
+    //Where the image starts within the padded cell.
+    let origin = [
+        padding + (content[0] - w) / 2,
+        padding + (content[1] - h) / 2,
+    ];
+
+    for y in origin[1] - padding..origin[1] + h + padding {
+        for x in origin[0] - padding..origin[0] + w + padding {
+            let sx = x.max(origin[0]).min(origin[0] + w - 1) - origin[0];
+            let sy = y.max(origin[1]).min(origin[1] + h - 1) - origin[1];
+            page.put_pixel(corner[0] + x, corner[1] + y, *tile.get_pixel(sx, sy));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_images_keep_their_size() {
+        let red = image::Rgba([255, 0, 0, 255]);
+        let tile = image::RgbaImage::from_pixel(2, 2, red);
+        let mut page = image::RgbaImage::new(10, 10);
+        blit_tile(&mut page, &tile, [0, 0], [6, 6], 2);
+
+        //The image is centered in the 6x6 tile at [4,4], and extruded out by the padding of 2.
+        for y in 0..10 {
+            for x in 0..10 {
+                let inside = (2..8).contains(&x) && (2..8).contains(&y);
+                let expected = if inside { red } else { image::Rgba([0; 4]) };
+                assert_eq!(*page.get_pixel(x, y), expected, "{} {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn pages_by_size_class() {
+        let sizes = [(4, 4), (64, 64), (3, 4), (60, 20), (40, 33), (0, 0)];
+        let images: Vec<image::RgbaImage> = sizes
+            .iter()
+            .map(|&(w, h)| image::RgbaImage::new(w, h))
+            .collect();
+        let (pages, handles) = layout(&images, 1, 1024);
+
+        //The tiny images share a page with tiles fit for them, not for the 64x64 one.
+        let small = handles[0].page;
+        assert_eq!(pages[small].content, [4, 4]);
+        assert_eq!((handles[2].page, handles[2].index), (small, 1));
+        assert_eq!(pages[handles[3].page].content, [60, 20]);
+        assert_eq!(pages[handles[4].page].content, [64, 64]);
+        assert_eq!(handles[1].page, handles[4].page);
+        assert_eq!(pages.len(), 4);
+
+        for (i, handle) in handles.iter().enumerate() {
+            assert_eq!(pages[handle.page].images[handle.index as usize], i);
+        }
+
+        //A class that does not fit in one page spills over into more.
+        let many = vec![image::RgbaImage::new(8, 8); 20];
+        let (pages, handles) = layout(&many, 1, 40);
+        assert_eq!(pages.len(), 2);
+        assert_eq!((pages[0].cols, pages[0].rows), (4, 4));
+        assert_eq!((pages[1].cols, pages[1].rows), (4, 1));
+        assert_eq!((handles[19].page, handles[19].index), (1, 3));
+    }
+}
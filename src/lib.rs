@@ -27,6 +27,10 @@
 //! to efficiently draw thousands of circles where each circle has a different color or radius.
 //! This was a design decision to make each vertex as lightweight as possible (just a x and y position),
 //! making it more efficient to set and send to the gpu.
+//! When that is not enough, the colored circle and rect sessions trade an extra 4 bytes per vertex
+//! for a packed RGBA8 color, so differently colored shapes can share a draw call.
+//! Likewise the sized circle and square sessions store a radius per point,
+//! unless every point ends up with the same radius, in which case the compact vertices are used.
 //!
 //! # Key Design Goals
 //!
@@ -53,11 +57,44 @@
 //! Shape                     | Representation                        | Opengl Primitive Type
 //! --------------------------|---------------------------------------|-----------------
 //! Circles                   | `(point,radius)`                      | POINTS
+//! Colored Circles           | `(point,radius,color)`                | POINTS
+//! Sized Circles             | `(point,radius)` per circle           | POINTS
+//! Rings                     | `(point,radius,width)`                | POINTS
+//! Axis Aligned Ellipses     | `(point,radius,ratio)`                | POINTS
 //! Axis Aligned Rectangles   | `(startx,endx,starty,endy)`           | TRIANGLES
+//! Colored Rectangles        | `(startx,endx,starty,endy,color)`     | TRIANGLES
+//! Rotated Rectangles        | `(center,half_extents,angle)`         | TRIANGLES
+//! Quadrilaterals            | `(corners)`                           | TRIANGLES
+//! Rounded Rectangles        | `(startx,endx,starty,endy,radius)`    | TRIANGLES
 //! Axis Aligned Squares      | `(point,radius)`                      | POINTS
+//! Sized Squares             | `(point,radius)` per square           | POINTS
 //! Lines                     | `(point,point,thickness)`             | TRIANGLES
 //! Arrows                    | `(point_start,point_end,thickness)`   | TRIANGLES 
+//! Polygons                  | `(outline,holes)`                     | TRIANGLES
+//! Regular Polygons          | `(center,radius,sides,rotation)`      | TRIANGLES
+//! Stars                     | `(center,radii,points,rotation)`      | TRIANGLES
+//! Polylines                 | `(points,thickness,join,cap)`         | TRIANGLES
+//! Arcs and Pie Sectors      | `(center,radius,start,sweep)`         | TRIANGLES
+//! Bezier Curves             | `(points,thickness)`                  | TRIANGLES
+//! Raw Triangles             | `(point,point,point)`                 | TRIANGLES
+//! Indexed Meshes            | `(vertices,indices)`                  | TRIANGLES
+//! Svg Paths and Shapes      | `(svg,scale,offset)`                  | TRIANGLES
+//! Paths                     | `move_to,line_to,..,fill,stroke`      | TRIANGLES
 //!   
+//! # Anti-aliasing
+//!
+//! Anti-aliasing can be turned on per draw by calling **`with_antialiasing(true)`**.
+//! Circles fade out over about a pixel around their edge.
+//! Lines, arrows and rects store an extra 4 bytes per vertex that say how far the vertex is
+//! from each edge of its shape, which is used to fade their edges out.
+//! Other shapes are unaffected.
+//!
+//! # Gradients
+//!
+//! Instead of a flat color, untextured shapes can be filled with a linear or radial gradient
+//! by calling **`with_linear_gradient()`**, **`with_linear_gradient_stops()`** or **`with_radial_gradient()`**.
+//! Gradients can have up to eight color stops and are evaluated per pixel, so no extra vertex data is needed.
+//!
 //! # Using Sprites
 //!
 //! This crate also allows the user to draw sprites. You can upload a tileset texture to the gpu and then draw thousands of sprites
@@ -65,13 +102,22 @@
 //! The sprites are point sprites drawn using the opengl POINTS primitive in order to cut down on the data
 //! that needs to be sent to the gpu.
 //!
+//! Drivers limit how big a point can be (`GL_ALIASED_POINT_SIZE_RANGE`).
+//! When a sprite, circle or square is drawn bigger than that, it is automatically drawn
+//! as an instanced quad made of two triangles instead, which looks the same.
+//! The same vertices are used either way.
+//!
 //! Each sprite vertex is composed of the following:
 //!
 //! * position:`[f32;2]`
 //! * index:`u16` - the user can index up to 256*256 different sprites in a tile set.
 //! * rotation:`u16` - this gets normalized to a float internally. The user passes a f32 float in radians.
+//! The lowest two bits are flags that mirror the sprite horizontally and vertically, set with `SpriteSession::with_flip()`.
+//! * scale:`[u16;2]` - how much the sprite is stretched along its x and y axis, in fixed point with 8 fractional bits.
+//! The user sets it with `SpriteSession::with_scale()`, so sprites of many sizes can be drawn in one draw call.
+//! * tint:`[u8;4]` - a color the sprite is multiplied by, set with `SpriteSession::with_tint()`.
 //!
-//! So each sprite vertex is compact at 4*3=12 bytes.
+//! So each sprite vertex is compact at 4*5=20 bytes.
 //!
 //! Each texture object has functions to create this index from a x and y coordinate.
 //! On the gpu, the index will be split into a x and y coordinate.
@@ -85,6 +131,24 @@
 //! the api. The rotation is in radians with 0 being no rotation and grows with a clockwise rotation.
 //! 
 //!
+//! # Texture atlases
+//!
+//! Many loose images can be packed into one texture with an `atlas::AtlasBuilder`, which is turned into
+//! textures with `WindowedSystem::atlas()`. Images are grouped into pages by size, rounding each side up to a power of two.
+//! Every image in a page gets a tile as big as the biggest image in it, so each page of the atlas
+//! is an ordinary tile set, and every image in a page can be drawn by one `SpriteSession`.
+//! Smaller images are centered in their tile with transparent pixels around them, so they are drawn at their own size.
+//! The edges of each image are extruded into a border of padding around it, so filtering does not blend it with its neighbours.
+//! The padding around the edge of each tile is left out when it is drawn.
+//! If the images do not all fit in one texture, more pages are made.
+//!
+//! # Animation
+//!
+//! The `animation` module has clips, which are sequences of tile indices that are each shown for some
+//! amount of time, and that loop, ping pong or play once. A `Player` keeps track of how far along a clip a sprite is,
+//! and gives back the tile index to pass to `SpriteSession::add()` for the current time.
+//! Clips do not touch the gpu, so one clip can be shared by every sprite that plays it.
+//!
 //! # Batch drawing
 //!
 //! While you can pretty efficiently draw thousands of objects by calling add() a bunch of times,
@@ -180,6 +244,7 @@ use glutin::PossiblyCurrent;
 use egaku2d_core;
 use egaku2d_core::gl;
 
+pub use egaku2d_core::animation;
 pub use egaku2d_core::batch;
 pub use egaku2d_core::shapes;
 pub use egaku2d_core::sprite;
@@ -188,6 +253,8 @@ pub use egaku2d_core::SimpleCanvas;
 use egaku2d_core::FixedAspectVec2;
 use egaku2d_core::AspectRatio;
 
+pub mod atlas;
+
 mod onein {
     use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
     static INSTANCES: AtomicUsize = AtomicUsize::new(0);
@@ -362,6 +429,11 @@ pub mod fullscreen {
             crate::texture(file, grid_dim)
         }
 
+        ///Packs the images of the builder into textures, a few pages for each size of image.
+        pub fn atlas(&mut self, builder: &atlas::AtlasBuilder) -> atlas::Atlas {
+            crate::atlas::build(builder)
+        }
+
         pub fn canvas(&self) -> &SimpleCanvas {
             &self.inner
         }
@@ -489,6 +561,13 @@ impl WindowedSystem {
         crate::texture(file, grid_dim)
     }
 
+    ///Packs the images of the builder into textures, a few pages for each size of image.
+    ///The fact that we need a mutable reference to this object
+    ///Ensures that we make the textures in the same thread.
+    pub fn atlas(&mut self, builder: &atlas::AtlasBuilder) -> atlas::Atlas {
+        atlas::build(builder)
+    }
+
     pub fn canvas(&self) -> &SimpleCanvas {
         &self.inner
     }
@@ -576,6 +655,12 @@ fn build_opengl_mipmapped_texture(width: u32, height: u32, image: image::RgbaIma
         gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
         gl_ok!();
 
+        //Can be changed per texture with Texture::set_wrap_mode().
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
+        gl_ok!();
+        gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
+        gl_ok!();
+
         gl::BindTexture(gl::TEXTURE_2D, 0);
         gl_ok!();
 